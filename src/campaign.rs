@@ -0,0 +1,114 @@
+use serde::{Serialize, Deserialize};
+use std::path::{Path, PathBuf};
+use crate::error::GameResult;
+use crate::player::InventoryLimits;
+use crate::save_load::read_json;
+
+/// One entry in the campaign manifest.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LevelDef {
+    pub id: String,
+    /// World file, relative to the manifest's directory
+    pub file: String,
+    pub title: String,
+    pub start_room: String,
+    #[serde(default)]
+    pub completion_flag: Option<String>,
+    #[serde(default)]
+    pub completion_text: Vec<String>,
+    #[serde(default)]
+    pub next: Option<String>,
+    /// Quests started on entering the level
+    #[serde(default)]
+    pub quests: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Campaign {
+    pub start: String,
+    pub levels: Vec<LevelDef>,
+    /// Carry limits for the whole campaign; unlimited when left out
+    #[serde(default)]
+    pub inventory: InventoryLimits,
+    #[serde(skip)]
+    pub base_dir: PathBuf,
+}
+
+impl Campaign {
+    pub fn level(&self, index: usize) -> Option<&LevelDef> {
+        self.levels.get(index)
+    }
+
+    pub fn level_index(&self, id: &str) -> Option<usize> {
+        self.levels.iter().position(|l| l.id == id)
+    }
+
+    pub fn start_index(&self) -> Option<usize> {
+        self.level_index(&self.start)
+    }
+
+    /// Finds a level by id, or by its position in the manifest
+    pub fn resolve_level(&self, key: &str) -> Option<usize> {
+        self.level_index(key).or_else(|| {
+            key.parse::<usize>()
+                .ok()
+                .filter(|&index| index < self.levels.len())
+        })
+    }
+
+    /// Full path to a level's world file
+    pub fn level_path(&self, level: &LevelDef) -> PathBuf {
+        self.base_dir.join(&level.file)
+    }
+}
+
+/// Loads the campaign manifest; level files are resolved next to it
+pub fn load_campaign(path: impl AsRef<Path>) -> GameResult<Campaign> {
+    let path = path.as_ref();
+    let mut campaign: Campaign = read_json(path)?;
+    campaign.base_dir = path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    Ok(campaign)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn campaign() -> Campaign {
+        serde_json::from_value(json!({
+            "start": "forest",
+            "levels": [
+                { "id": "tutorial", "file": "tutorial.json", "title": "Tutorial", "start_room": "hall", "next": "forest" },
+                { "id": "forest", "file": "forest.json", "title": "Forest", "start_room": "clearing" }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn resolves_levels_by_id_or_position() {
+        let campaign = campaign();
+        assert_eq!(campaign.start_index(), Some(1));
+        assert_eq!(campaign.resolve_level("tutorial"), Some(0));
+        assert_eq!(campaign.resolve_level("1"), Some(1));
+        assert_eq!(campaign.resolve_level("2"), None);
+        assert_eq!(campaign.resolve_level("desert"), None);
+    }
+
+    #[test]
+    fn level_files_sit_next_to_the_manifest() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/campaign.json");
+        let campaign = load_campaign(&path).unwrap();
+
+        let start = campaign.level(campaign.start_index().unwrap()).unwrap();
+        assert!(campaign.level_path(start).starts_with(path.parent().unwrap()));
+        assert!(campaign.level_path(start).exists());
+        for level in &campaign.levels {
+            assert!(level.next.as_ref().is_none_or(|next| campaign.level_index(next).is_some()), "{}", level.id);
+        }
+    }
+}
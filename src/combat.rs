@@ -1,95 +1,510 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use rand::Rng;
+use crate::effects;
 use crate::player::Player;
-use crate::enemies::Enemy;
-use crate::items::ItemType;
-use std::process;
-
-pub fn start_combat(player: &mut Player, enemy: &mut Enemy, previous_room: &str) -> bool {
-    println!("⚔️ You encounter a {}!", enemy.name);
-    println!("{}", enemy.description);
-
-    loop {
-        println!(
-            "\n❤️ Your HP: {} | 💀 {}’s HP: {}",
-            player.health, enemy.name, enemy.health
-        );
-        println!("Choose an action (attack / heal / defend / run):");
-
-        let action = crate::utils::get_input().to_lowercase();
-
-        let valid_action = match action.as_str() {
-            "attack" => {
-                let damage = player.attack_damage();
-                enemy.health -= damage;
-                println!("You strike the {} for {} damage!", enemy.name, damage);
-
-                if enemy.health <= 0 {
-                    println!("🎉 You defeated the {}!", enemy.name);
-
-                    let xp_gain = enemy.attack * 5;
-                    player.add_xp(xp_gain);
-                    return false;
+use crate::effects::Affliction;
+use crate::enemies::{Ability, AbilityEffect, Enemy};
+use crate::items::{self, ItemType};
+use crate::commands::Command;
+use crate::events::GameEvent;
+
+// Combat tuning
+const PLAYER_HIT_CHANCE: f64 = 0.9;
+const PLAYER_CRIT_CHANCE: f64 = 0.1;
+const ENEMY_HIT_CHANCE: f64 = 0.85;
+const ENEMY_CRIT_CHANCE: f64 = 0.05;
+const DAMAGE_VARIANCE_PERCENT: i32 = 20;
+const CRIT_MULTIPLIER: i32 = 2;
+
+/// A landed blow: final damage and whether it was a critical hit
+struct Hit {
+    damage: i32,
+    critical: bool,
+}
+
+/// Rolls hit chance, damage variance and criticals for one attack
+fn roll_attack(base: i32, hit_chance: f64, crit_chance: f64, rng: &mut impl Rng) -> Option<Hit> {
+    if !rng.gen_bool(hit_chance) {
+        return None;
+    }
+
+    let percent = rng.gen_range(100 - DAMAGE_VARIANCE_PERCENT..=100 + DAMAGE_VARIANCE_PERCENT);
+    let mut damage = (base * percent / 100).max(1);
+
+    let critical = rng.gen_bool(crit_chance);
+    if critical {
+        damage *= CRIT_MULTIPLIER;
+    }
+
+    Some(Hit { damage, critical })
+}
+
+/// A fight in progress. Plain data, so it can be saved mid-fight.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CombatState {
+    pub enemy: Enemy,
+    /// Room the fight takes place in
+    pub room: String,
+    /// Where the player ends up after fleeing
+    pub previous_room: String,
+    #[serde(default)]
+    pub turn: u32,
+    /// Set by defending; softens the enemy's next hit
+    #[serde(default)]
+    pub guarding: bool,
+    /// The enemy's health when the fight began
+    #[serde(default)]
+    pub enemy_max_health: i32,
+    /// Set by the enemy's defend ability; softens the player's next hit
+    #[serde(default)]
+    pub enemy_guarding: bool,
+    /// Times each ability has been used, by name
+    #[serde(default)]
+    pub ability_uses: HashMap<String, u32>,
+    /// Enemy called in by a summon, waiting to take over the room
+    #[serde(default)]
+    pub summoned: Option<String>,
+}
+
+/// Player inputs that advance a fight
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CombatAction {
+    Attack,
+    Heal,
+    Defend,
+    Flee,
+}
+
+/// Where a fight stands after a turn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CombatOutcome {
+    Ongoing,
+    EnemyDefeated,
+    PlayerDefeated,
+    Fled,
+    EnemyFled,
+}
+
+impl CombatAction {
+    pub fn from_command(command: &Command) -> Option<Self> {
+        match command {
+            Command::Attack => Some(CombatAction::Attack),
+            Command::Heal => Some(CombatAction::Heal),
+            Command::Defend => Some(CombatAction::Defend),
+            Command::Run => Some(CombatAction::Flee),
+            _ => None,
+        }
+    }
+}
+
+impl CombatState {
+    pub fn new(enemy: Enemy, room: &str, previous_room: &str, events: &mut Vec<GameEvent>) -> Self {
+        events.push(GameEvent::EnemyAppears {
+            name: enemy.name.clone(),
+            description: enemy.description.clone(),
+        });
+
+        Self {
+            enemy_max_health: enemy.health,
+            enemy,
+            room: room.to_string(),
+            previous_room: previous_room.to_string(),
+            turn: 0,
+            guarding: false,
+            enemy_guarding: false,
+            ability_uses: HashMap::new(),
+            summoned: None,
+        }
+    }
+
+    // Saves from before the field existed fall back to current health
+    pub fn enemy_max_health(&self) -> i32 {
+        self.enemy_max_health.max(self.enemy.health)
+    }
+
+    /// Resolves one player action and the enemy's response
+    pub fn advance(
+        &mut self,
+        action: CombatAction,
+        player: &mut Player,
+        rng: &mut impl Rng,
+        events: &mut Vec<GameEvent>,
+    ) -> CombatOutcome {
+        self.turn += 1;
+        let enemy_max_health = self.enemy_max_health();
+        let enemy = &mut self.enemy;
+        let enemy_target = Some(enemy.name.clone());
+
+        // The player's effects run at the start of the round;
+        // a stunned player loses the action they picked
+        let stunned = effects::is_stunned(&player.effects, &None, events);
+        effects::tick(&mut player.effects, &mut player.health, player.max_health, true, &None, events);
+        if player.health <= 0 {
+            events.push(GameEvent::PlayerDefeated);
+            return CombatOutcome::PlayerDefeated;
+        }
+        let action = if stunned { None } else { Some(action) };
+
+        match action {
+            Some(CombatAction::Attack) => {
+                let roll = roll_attack(player.attack_damage(), PLAYER_HIT_CHANCE, PLAYER_CRIT_CHANCE, rng);
+                let enemy_guarding = std::mem::take(&mut self.enemy_guarding);
+                match roll {
+                    Some(mut hit) => {
+                        if enemy_guarding {
+                            hit.damage = (hit.damage / 2).max(1);
+                            events.push(GameEvent::EnemyGuarded { enemy: enemy.name.clone() });
+                        }
+                        enemy.health -= hit.damage;
+                        events.push(GameEvent::DamageDealt {
+                            enemy: enemy.name.clone(),
+                            damage: hit.damage,
+                            critical: hit.critical,
+                        });
+                        if let Some(weapon) = player.equipment.weapon.as_ref().filter(|_| enemy.health > 0) {
+                            effects::afflict(&weapon.inflicts, &mut enemy.effects, &enemy_target, rng, events);
+                        }
+                    }
+                    None => events.push(GameEvent::AttackMissed { enemy: enemy.name.clone() }),
                 }
-                true
             }
 
-            "heal" => {
+            Some(CombatAction::Heal) => {
                 if let Some(pos) = player
                     .inventory
                     .iter()
                     .position(|i| matches!(i.item_type, ItemType::Healing))
                 {
-                    let item = player.inventory.remove(pos);
+                    let item = items::take_from_stack(&mut player.inventory, pos, 1);
                     if let Some(amount) = item.power {
-                        player.heal(amount);
-                    } else {
-                        println!("💖 You use {}, but it had no effect.", item.name);
+                        player.heal(amount, events);
+                    }
+                    if !player.apply_item_effects(&item, events) && item.power.is_none() {
+                        events.push(GameEvent::ItemNoEffect(item.name));
                     }
                 } else {
-                    println!("You have no healing items!");
+                    events.push(GameEvent::NoHealingItems);
                 }
-                true
             }
 
-            "defend" => {
-                println!("🛡️ You brace yourself!");
-                true
+            Some(CombatAction::Defend) => {
+                self.guarding = true;
+                events.push(GameEvent::Braced);
             }
 
-            "run" => {
-                println!("🏃 You flee from the battle!");
-                player.current_room = previous_room.to_string();
-                return true;
+            Some(CombatAction::Flee) => {
+                player.current_room = self.previous_room.clone();
+                events.push(GameEvent::Fled { room: player.current_room.clone() });
+                return CombatOutcome::Fled;
             }
 
-            _ => {
-                println!("Unknown action. Type attack / heal / defend / run.");
-                false
+            None => {}
+        }
+
+        // The enemy's effects run at the start of its turn
+        let enemy_stunned = enemy.health > 0 && effects::is_stunned(&enemy.effects, &enemy_target, events);
+        if enemy.health > 0 {
+            effects::tick(&mut enemy.effects, &mut enemy.health, enemy_max_health, true, &enemy_target, events);
+        }
+        if enemy.health <= 0 {
+            events.push(GameEvent::EnemyDefeated(enemy.name.clone()));
+
+            let xp_gain = enemy.xp_reward;
+            player.add_xp(xp_gain, events);
+            return CombatOutcome::EnemyDefeated;
+        }
+
+        let guarding = std::mem::take(&mut self.guarding);
+        self.enemy_guarding = false;
+        if !enemy_stunned {
+            if let Some(outcome) = self.enemy_turn(player, guarding, rng, events) {
+                return outcome;
             }
+        }
+
+        CombatOutcome::Ongoing
+    }
+
+    // Carries out the enemy's move; Some when the move ends the fight
+    fn enemy_turn(
+        &mut self,
+        player: &mut Player,
+        guarding: bool,
+        rng: &mut impl Rng,
+        events: &mut Vec<GameEvent>,
+    ) -> Option<CombatOutcome> {
+        let Some(ability) = self.pick_ability(rng) else {
+            self.strike(100, &[], player, guarding, rng, events);
+            return None;
         };
 
-        // Only attack if player enetered a valid input and enemy is alive
-        if valid_action && enemy.health > 0 {
-            let damage = enemy.attack;
-            player.health -= damage;
-            println!("The {} attacks you for {} damage!", enemy.name, damage);
-
-            if player.health <= 0 {
-                println!("\n💀 You have been defeated!\n");
-                println!(
-"⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢀⡀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⢀⣀⠀⠀⠀⣶⡆⠀⣰⣿⠇⣾⡿⠛⠉⠁
-⠀⣠⣴⠾⠿⠿⠀⢀⣾⣿⣆⣀⣸⣿⣷⣾⣿⡿⢸⣿⠟⢓⠀⠀
-⣴⡟⠁⣀⣠⣤⠀⣼⣿⠾⣿⣻⣿⠃⠙⢫⣿⠃⣿⡿⠟⠛⠁⠀
-⢿⣝⣻⣿⡿⠋⠾⠟⠁⠀⠹⠟⠛⠀⠀⠈⠉⠀⠉⠀⠀⠀⠀⠀
-⠀⠉⠉⠁⠀⠀⠀⠀⠀⠀⠀⠀⠀⡀⠀⠀⣀⢀⣠⣤⣴⣤⣄⠀
-⠀⠀⠀⠀⣀⣤⣤⢶⣤⠀⠀⢀⣴⢃⣿⠟⠋⢹⣿⣣⣴⡿⠋⠀
-⠀⠀⣰⣾⠟⠉⣿⡜⣿⡆⣴⡿⠁⣼⡿⠛⢃⣾⡿⠋⢻⣇⠀⠀
-⠀⠐⣿⡁⢀⣠⣿⡇⢹⣿⡿⠁⢠⣿⠷⠟⠻⠟⠀⠀⠈⠛⠀⠀
-⠀⠀⠙⠻⠿⠟⠋⠀⠀⠙⠁⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀"
-                );
-                println!("\nGame Over. Thanks for playing Adventurer!\nAnother shall be sent to complete what you have failed in.");
-                process::exit(0);
+        *self.ability_uses.entry(ability.name.clone()).or_default() += 1;
+        let name = self.enemy.name.clone();
+        events.push(GameEvent::EnemyAbility {
+            enemy: name.clone(),
+            ability: ability.name,
+            message: ability.message,
+        });
+
+        match ability.effect {
+            AbilityEffect::Strike { percent, inflicts } => {
+                self.strike(percent, &inflicts, player, guarding, rng, events);
+            }
+            AbilityEffect::Heal { amount } => {
+                let healed = amount.min(self.enemy_max_health() - self.enemy.health).max(0);
+                self.enemy.health += healed;
+                events.push(GameEvent::EnemyHealed { enemy: name, amount: healed, health: self.enemy.health });
+            }
+            AbilityEffect::Defend => {
+                self.enemy_guarding = true;
+                events.push(GameEvent::EnemyBraced { enemy: name });
+            }
+            AbilityEffect::Summon { enemy } => {
+                events.push(GameEvent::EnemySummoned { enemy: name, summoned: enemy.clone() });
+                self.summoned = Some(enemy);
+            }
+            AbilityEffect::Flee => {
+                events.push(GameEvent::EnemyFled { enemy: name });
+                return Some(CombatOutcome::EnemyFled);
             }
         }
+        None
+    }
+
+    /// Picks one of the enemy's usable abilities by weight; None means a plain attack
+    fn pick_ability(&self, rng: &mut impl Rng) -> Option<Ability> {
+        // Enemies without abilities don't touch the RNG, so old seeds replay the same
+        if self.enemy.abilities.is_empty() {
+            return None;
+        }
+
+        let health_percent = self.enemy.health * 100 / self.enemy_max_health().max(1);
+        let usable: Vec<&Ability> = self
+            .enemy
+            .abilities
+            .iter()
+            .filter(|a| a.below_health.is_none_or(|limit| health_percent <= limit))
+            .filter(|a| a.uses.is_none_or(|max| self.ability_uses.get(&a.name).copied().unwrap_or(0) < max))
+            .collect();
+
+        let total = self.enemy.attack_weight + usable.iter().map(|a| a.weight).sum::<u32>();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0..total);
+        if roll < self.enemy.attack_weight {
+            return None;
+        }
+        roll -= self.enemy.attack_weight;
+        for ability in usable {
+            if roll < ability.weight {
+                return Some(ability.clone());
+            }
+            roll -= ability.weight;
+        }
+        None
+    }
+
+    // An enemy attack at `percent` of its attack stat
+    fn strike(
+        &mut self,
+        percent: i32,
+        inflicts: &[Affliction],
+        player: &mut Player,
+        guarding: bool,
+        rng: &mut impl Rng,
+        events: &mut Vec<GameEvent>,
+    ) {
+        let enemy = &self.enemy;
+        let base = (enemy.attack * percent / 100).max(1);
+        let Some(hit) = roll_attack(base, ENEMY_HIT_CHANCE, ENEMY_CRIT_CHANCE, rng) else {
+            events.push(GameEvent::EnemyMissed { enemy: enemy.name.clone() });
+            return;
+        };
+
+        let damage = player.mitigate(hit.damage, guarding);
+        player.health -= damage;
+        events.push(GameEvent::DamageTaken {
+            enemy: enemy.name.clone(),
+            damage,
+            blocked: hit.damage - damage,
+            critical: hit.critical,
+        });
+        effects::afflict(&enemy.inflicts, &mut player.effects, &None, rng, events);
+        effects::afflict(inflicts, &mut player.effects, &None, rng, events);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+    use serde_json::json;
+    use crate::effects::{EffectKind, StatusEffect};
+
+    fn enemy(extra: serde_json::Value) -> Enemy {
+        let mut fields = json!({
+            "name": "Goblin",
+            "description": "A sneaky little creature.",
+            "health": 30,
+            "attack": 5,
+            "xp_reward": 10
+        });
+        fields.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(fields).unwrap()
+    }
+
+    // Runs attacks until the fight ends, returning the outcome and every event
+    fn fight(seed: u64, enemy: Enemy) -> (CombatOutcome, Player, Vec<String>) {
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        let mut player = Player::new();
+        let mut events = Vec::new();
+        let mut combat = CombatState::new(enemy, "hall", "gate", &mut events);
+
+        let mut outcome = CombatOutcome::Ongoing;
+        while outcome == CombatOutcome::Ongoing && combat.turn < 100 {
+            outcome = combat.advance(CombatAction::Attack, &mut player, &mut rng, &mut events);
+        }
+        (outcome, player, events.iter().map(|e| format!("{:?}", e)).collect())
+    }
+
+    #[test]
+    fn fleeing_player_returns_to_previous_room() {
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+        let mut player = Player::new();
+        let mut events = Vec::new();
+        let mut combat = CombatState::new(enemy(json!({})), "hall", "gate", &mut events);
+
+        let outcome = combat.advance(CombatAction::Flee, &mut player, &mut rng, &mut events);
+        assert_eq!(outcome, CombatOutcome::Fled);
+        assert_eq!(player.current_room, "gate");
+    }
+
+    #[test]
+    fn attacking_wears_the_enemy_down() {
+        let (outcome, player, _) = fight(5, enemy(json!({})));
+        assert_eq!(outcome, CombatOutcome::EnemyDefeated);
+        assert!(player.health > 0);
+    }
+
+    #[test]
+    fn same_seed_replays_the_same_fight() {
+        let (outcome, player, events) = fight(42, enemy(json!({})));
+        let (replayed, replayed_player, replayed_events) = fight(42, enemy(json!({})));
+
+        assert_eq!(outcome, replayed);
+        assert_eq!(player.health, replayed_player.health);
+        assert_eq!(events, replayed_events);
+    }
+
+    #[test]
+    fn defeating_the_enemy_grants_xp() {
+        let (outcome, player, events) = fight(7, enemy(json!({})));
+
+        assert_eq!(outcome, CombatOutcome::EnemyDefeated);
+        assert_eq!(player.xp, 10);
+        assert!(events.iter().any(|e| e == "EnemyDefeated(\"Goblin\")"));
+    }
+
+    #[test]
+    fn a_saved_fight_carries_on_where_it_left_off() {
+        let mut rng = ChaCha12Rng::seed_from_u64(9);
+        let mut player = Player::new();
+        let mut events = Vec::new();
+        let mut combat = CombatState::new(enemy(json!({})), "hall", "gate", &mut events);
+        combat.advance(CombatAction::Defend, &mut player, &mut rng, &mut events);
+
+        let saved: CombatState = serde_json::from_str(&serde_json::to_string(&combat).unwrap()).unwrap();
+        assert_eq!(saved.turn, 1);
+        assert_eq!(saved.enemy.health, combat.enemy.health);
+        assert_eq!(saved.room, "hall");
+        assert_eq!(saved.previous_room, "gate");
+    }
+
+    #[test]
+    fn a_stun_costs_the_player_exactly_its_turns() {
+        let mut rng = ChaCha12Rng::seed_from_u64(3);
+        let mut player = Player::new();
+        player.effects.push(StatusEffect { kind: EffectKind::Stun, turns: 1, power: 0 });
+        let mut events = Vec::new();
+        let mut combat = CombatState::new(enemy(json!({})), "hall", "gate", &mut events);
+
+        combat.advance(CombatAction::Attack, &mut player, &mut rng, &mut events);
+        assert!(events.iter().any(|e| matches!(e, GameEvent::Stunned { target: None })));
+        assert_eq!(combat.enemy.health, 30);
+        assert!(player.effects.is_empty());
+
+        events.clear();
+        combat.advance(CombatAction::Attack, &mut player, &mut rng, &mut events);
+        assert!(!events.iter().any(|e| matches!(e, GameEvent::Stunned { target: None })));
+        assert!(events.iter().any(|e| matches!(e, GameEvent::DamageDealt { .. } | GameEvent::AttackMissed { .. })));
+    }
+
+    fn picks(combat: &CombatState, seed: u64, rolls: usize) -> Vec<Option<String>> {
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        (0..rolls).map(|_| combat.pick_ability(&mut rng).map(|a| a.name)).collect()
+    }
+
+    #[test]
+    fn abilities_are_picked_by_weight() {
+        let shaman = enemy(json!({
+            "attack_weight": 10,
+            "abilities": [
+                { "name": "Hex", "type": "strike", "weight": 20 },
+                { "name": "Mend", "type": "heal", "amount": 5, "weight": 10 }
+            ]
+        }));
+        let combat = CombatState::new(shaman, "hall", "gate", &mut Vec::new());
+
+        let picks = picks(&combat, 11, 4000);
+        let count = |name: Option<&str>| picks.iter().filter(|p| p.as_deref() == name).count();
+        // Expected 1000, 2000 and 1000 out of 4000
+        assert!((800..1200).contains(&count(None)), "{}", count(None));
+        assert!((1800..2200).contains(&count(Some("Hex"))), "{}", count(Some("Hex")));
+        assert!((800..1200).contains(&count(Some("Mend"))), "{}", count(Some("Mend")));
+    }
+
+    #[test]
+    fn used_up_and_low_health_abilities_are_gated() {
+        let shaman = enemy(json!({
+            "attack_weight": 0,
+            "abilities": [
+                { "name": "Hex", "type": "strike", "uses": 1 },
+                { "name": "Mend", "type": "heal", "amount": 5, "below_health": 50 }
+            ]
+        }));
+        let mut combat = CombatState::new(shaman, "hall", "gate", &mut Vec::new());
+
+        assert!(picks(&combat, 2, 50).iter().all(|p| p.as_deref() == Some("Hex")));
+
+        combat.ability_uses.insert("Hex".to_string(), 1);
+        assert!(picks(&combat, 2, 50).iter().all(Option::is_none));
+
+        combat.enemy.health = 15;
+        assert!(picks(&combat, 2, 50).iter().all(|p| p.as_deref() == Some("Mend")));
+    }
+
+    #[test]
+    fn using_an_ability_counts_against_its_uses() {
+        let shaman = enemy(json!({
+            "attack_weight": 0,
+            "abilities": [{ "name": "Ward", "type": "defend", "uses": 2 }]
+        }));
+        let mut rng = ChaCha12Rng::seed_from_u64(9);
+        let mut player = Player::new();
+        let mut events = Vec::new();
+        let mut combat = CombatState::new(shaman, "hall", "gate", &mut events);
+
+        for _ in 0..4 {
+            combat.advance(CombatAction::Defend, &mut player, &mut rng, &mut events);
+        }
+
+        let braced = events.iter().filter(|e| matches!(e, GameEvent::EnemyBraced { .. })).count();
+        assert_eq!(braced, 2);
+        assert_eq!(combat.ability_uses["Ward"], 2);
+    }
+}
+
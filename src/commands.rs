@@ -1,13 +1,38 @@
+#[derive(Clone, Debug, PartialEq)]
 pub enum Command {
     Help,
     Status,
     Go(String),
     Look,
-    Take(String),
-    Use(String),
+    Examine(String),
+    /// Item name and how many; no count means the command's default
+    Take(String, Option<u32>),
+    TakeFrom { item: String, count: Option<u32>, container: String },
+    Use(String, Option<u32>),
+    Drop(String, Option<u32>),
+    Open(String),
+    Search(String),
+    Put { item: String, count: Option<u32>, container: String },
+    Equip(String),
+    Unequip(String),
     Inventory,
-    Save,
-    Load,
+    Journal,
+    Talk(String),
+    /// A numbered answer in a conversation
+    Choose(usize),
+    Bye,
+    List,
+    Buy(String, Option<u32>),
+    Sell(String, Option<u32>),
+    Craft(String),
+    Recipes,
+    Save(String),
+    Load(String),
+    Saves,
+    Attack,
+    Heal,
+    Defend,
+    Run,
     Quit,
     Unknown(String),
 }
@@ -22,26 +47,83 @@ pub fn parse_command(input: &str) -> Command {
         "status" => Command::Status,
         "go" | "move" => Command::Go(arg),
         "look" => Command::Look,
-        "take" => Command::Take(arg),
-        "use" => Command::Use(arg),
+        "examine" | "inspect" | "x" => Command::Examine(arg),
+        "take" => match arg.rsplit_once(" from ") {
+            Some((item, container)) => {
+                let (item, count) = split_count(item.trim());
+                Command::TakeFrom { item, count, container: container.trim().to_string() }
+            }
+            None => {
+                let (item, count) = split_count(&arg);
+                Command::Take(item, count)
+            }
+        },
+        "open" => Command::Open(arg),
+        "search" => Command::Search(arg),
+        "drop" => {
+            let (item, count) = split_count(&arg);
+            Command::Drop(item, count)
+        }
+        "put" => match arg.rsplit_once(" in ") {
+            Some((item, container)) => {
+                let (item, count) = split_count(item.trim());
+                Command::Put { item, count, container: container.trim().to_string() }
+            }
+            // Without a container, putting down is dropping
+            None => {
+                let (item, count) = split_count(&arg);
+                Command::Drop(item, count)
+            }
+        },
+        "use" => {
+            let (item, count) = split_count(&arg);
+            Command::Use(item, count)
+        }
+        "equip" | "wear" | "wield" => Command::Equip(arg),
+        "unequip" | "remove" => Command::Unequip(arg),
         "inventory" | "inv" => Command::Inventory,
-        "save" => Command::Save,
-        "load" => Command::Load,
+        "journal" | "quests" => Command::Journal,
+        "talk" | "speak" => {
+            let target = arg.strip_prefix("to ").unwrap_or(&arg);
+            Command::Talk(target.strip_prefix("the ").unwrap_or(target).trim().to_string())
+        }
+        "bye" | "goodbye" | "leave" => Command::Bye,
+        "list" | "shop" | "wares" => Command::List,
+        "craft" | "brew" | "make" => Command::Craft(arg),
+        "recipes" => Command::Recipes,
+        "buy" => {
+            let (item, count) = split_count(&arg);
+            Command::Buy(item, count)
+        }
+        "sell" => {
+            let (item, count) = split_count(&arg);
+            Command::Sell(item, count)
+        }
+        number if number.parse::<usize>().is_ok() && arg.is_empty() => {
+            Command::Choose(number.parse().unwrap_or_default())
+        }
+        "save" => Command::Save(arg),
+        "load" => Command::Load(arg),
+        "saves" => Command::Saves,
+        "attack" => Command::Attack,
+        "heal" => Command::Heal,
+        "defend" => Command::Defend,
+        "run" | "flee" => Command::Run,
         "quit" | "exit" => Command::Quit,
         _ => Command::Unknown(input.to_string()),
     }
 }
 
-pub fn print_help() {
-    println!("📝 Available Commands:");
-    println!("  help             - Show this help message");
-    println!("  status           - Show your current player status");
-    println!("  go <direction>   - Move in a direction (north, south, east, west)");
-    println!("  look             - Look around the current room");
-    println!("  take <item>      - Pick up an item");
-    println!("  use <item>       - Use an item from your inventory");
-    println!("  inventory / inv  - Show your inventory");
-    println!("  save             - Save your game progress");
-    println!("  load             - Load a saved game");
-    println!("  quit / exit      - Quit the game");
+/// Splits a leading count off an item name: "3 healing herb" or "all healing herb"
+fn split_count(arg: &str) -> (String, Option<u32>) {
+    if let Some((first, rest)) = arg.split_once(' ') {
+        let count = match first {
+            "all" => Some(u32::MAX),
+            n => n.parse::<u32>().ok().filter(|&n| n > 0),
+        };
+        if count.is_some() {
+            return (rest.trim().to_string(), count);
+        }
+    }
+    (arg.to_string(), None)
 }
@@ -0,0 +1,273 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::path::Path;
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+use crate::error::{GameError, GameResult};
+use crate::events::{GameEvent, RecipeView};
+use crate::items::{self, Item};
+use crate::player::{InventoryLimits, Player};
+use crate::save_load::read_asset;
+
+/// Turns a set of inventory items into a new one
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Recipe {
+    pub name: String,
+    #[serde(default)]
+    pub inputs: Vec<Ingredient>,
+    #[serde(deserialize_with = "items::deserialize_item")]
+    pub output: Item,
+    /// Room id the recipe can only be made in
+    #[serde(default)]
+    pub room: Option<String>,
+    /// Item that must be carried, but isn't used up
+    #[serde(default)]
+    pub tool: Option<String>,
+    /// Known from the start instead of learned
+    #[serde(default)]
+    pub known: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Ingredient {
+    /// Item name
+    pub item: String,
+    #[serde(default = "default_count")]
+    pub count: u32,
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecipeData {
+    pub recipes: HashMap<String, Recipe>,
+}
+
+// Global storage for all recipes, keyed by id
+pub static RECIPE_LIST: Lazy<RwLock<HashMap<String, Recipe>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Loads recipes from JSON into global memory. Items must already be loaded.
+pub fn load_recipes(path: impl AsRef<Path>) -> GameResult<HashMap<String, Recipe>> {
+    let recipe_data: RecipeData = read_asset(path)?;
+
+    let mut global_recipes = RECIPE_LIST.write().unwrap();
+    *global_recipes = recipe_data.recipes.clone();
+
+    Ok(recipe_data.recipes)
+}
+
+/// Get a cloned recipe by id
+pub fn get_recipe(id: &str) -> Option<Recipe> {
+    let recipes = RECIPE_LIST.read().unwrap();
+    recipes.get(id).cloned()
+}
+
+/// Ids and recipes the player can make, sorted by name
+fn known_recipes(player: &Player) -> Vec<(String, Recipe)> {
+    let recipes = RECIPE_LIST.read().unwrap();
+    let mut known: Vec<(String, Recipe)> = recipes
+        .iter()
+        .filter(|(id, recipe)| recipe.known || player.recipes.contains(id))
+        .map(|(id, recipe)| (id.clone(), recipe.clone()))
+        .collect();
+    known.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+    known
+}
+
+/// Teaches the player a recipe; returns false if it was already known
+pub fn learn(id: &str, player: &mut Player, events: &mut Vec<GameEvent>) -> bool {
+    let Some(recipe) = get_recipe(id) else {
+        events.push(GameEvent::Error(GameError::MissingRecipe(id.to_string()).to_string()));
+        return false;
+    };
+    if recipe.known || player.recipes.iter().any(|r| r == id) {
+        events.push(GameEvent::RecipeAlreadyKnown(recipe.name));
+        return false;
+    }
+
+    player.recipes.push(id.to_string());
+    events.push(GameEvent::RecipeLearned(recipe.name));
+    true
+}
+
+/// Lists every recipe the player knows
+pub fn recipes(player: &Player) -> Vec<RecipeView> {
+    known_recipes(player)
+        .into_iter()
+        .map(|(_, recipe)| RecipeView {
+            name: recipe.name,
+            inputs: recipe.inputs.into_iter().map(|i| (i.item, i.count)).collect(),
+            output: (recipe.output.name, recipe.output.quantity),
+            room: recipe.room,
+            tool: recipe.tool,
+        })
+        .collect()
+}
+
+/// Makes a known recipe, by id or name, from the player's inventory
+pub fn craft(target: &str, limits: &InventoryLimits, player: &mut Player, events: &mut Vec<GameEvent>) {
+    let Some((_, recipe)) = known_recipes(player)
+        .into_iter()
+        .find(|(id, recipe)| id.eq_ignore_ascii_case(target) || recipe.name.eq_ignore_ascii_case(target))
+    else {
+        events.push(GameEvent::UnknownRecipe(target.to_string()));
+        return;
+    };
+
+    if let Some(room) = &recipe.room {
+        if player.current_room != *room {
+            events.push(GameEvent::CraftNeedsRoom { recipe: recipe.name, room: room.clone() });
+            return;
+        }
+    }
+    if let Some(tool) = &recipe.tool {
+        if items::find_item(&player.inventory, tool).is_none() {
+            events.push(GameEvent::CraftNeedsTool { recipe: recipe.name, tool: tool.clone() });
+            return;
+        }
+    }
+
+    let missing: Vec<(String, u32)> = recipe
+        .inputs
+        .iter()
+        .filter_map(|input| {
+            let have = items::count_of(&player.inventory, &input.item);
+            (have < input.count).then(|| (input.item.clone(), input.count - have))
+        })
+        .collect();
+    if !missing.is_empty() {
+        events.push(GameEvent::MissingIngredients { recipe: recipe.name, missing });
+        return;
+    }
+
+    // Work on a copy, so nothing is used up if the result won't fit.
+    // Inputs may be spread over several entries of the same name.
+    let mut after = player.clone();
+    for input in &recipe.inputs {
+        let mut left = input.count;
+        while left > 0 {
+            let Some(pos) = items::find_item(&after.inventory, &input.item) else { break };
+            left -= items::take_from_stack(&mut after.inventory, pos, left).quantity;
+        }
+    }
+    for _ in 0..recipe.output.quantity {
+        let unit = Item { quantity: 1, ..recipe.output.clone() };
+        if !after.can_carry(&unit, limits) {
+            events.push(GameEvent::InventoryFull(recipe.output.name));
+            return;
+        }
+        after.add_item(unit);
+    }
+
+    player.inventory = after.inventory;
+    events.push(GameEvent::Crafted { item: recipe.output.name, count: recipe.output.quantity });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    // The registry is shared by every test, so each test registers a recipe of its own
+    fn register(id: &str, recipe: Value) {
+        let recipe: Recipe = serde_json::from_value(recipe).unwrap();
+        RECIPE_LIST.write().unwrap().insert(id.to_string(), recipe);
+    }
+
+    fn item(name: &str, quantity: u32) -> Item {
+        serde_json::from_value(json!({
+            "name": name, "item_type": "Utility", "power": null, "usable_on": null,
+            "stackable": true, "quantity": quantity, "weight": 1
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn crafting_uses_up_the_inputs() {
+        register("craft_test_rope", json!({
+            "name": "Craft Test Rope",
+            "known": true,
+            "inputs": [{ "item": "Craft Test Fibre", "count": 3 }],
+            "output": item("Craft Test Rope", 1)
+        }));
+        let mut player = Player::new();
+        player.add_item(item("Craft Test Fibre", 4));
+        let mut events = Vec::new();
+
+        craft("craft test rope", &InventoryLimits::default(), &mut player, &mut events);
+
+        assert_eq!(items::count_of(&player.inventory, "Craft Test Fibre"), 1);
+        assert_eq!(items::count_of(&player.inventory, "Craft Test Rope"), 1);
+    }
+
+    #[test]
+    fn nothing_is_used_up_when_an_ingredient_is_missing() {
+        register("craft_test_torch", json!({
+            "name": "Craft Test Torch",
+            "known": true,
+            "inputs": [{ "item": "Craft Test Stick" }, { "item": "Craft Test Oil", "count": 2 }],
+            "output": item("Craft Test Torch", 1)
+        }));
+        let mut player = Player::new();
+        player.add_item(item("Craft Test Stick", 1));
+        let mut events = Vec::new();
+
+        craft("craft_test_torch", &InventoryLimits::default(), &mut player, &mut events);
+
+        assert_eq!(items::count_of(&player.inventory, "Craft Test Stick"), 1);
+        assert!(matches!(
+            &events[..],
+            [GameEvent::MissingIngredients { missing, .. }] if missing == &[("Craft Test Oil".to_string(), 2)]
+        ));
+    }
+
+    #[test]
+    fn nothing_is_used_up_when_the_result_would_not_fit() {
+        register("craft_test_arrows", json!({
+            "name": "Craft Test Arrows",
+            "known": true,
+            "inputs": [{ "item": "Craft Test Shaft" }],
+            "output": item("Craft Test Arrow", 5)
+        }));
+        let mut player = Player::new();
+        player.add_item(item("Craft Test Shaft", 2));
+        let mut events = Vec::new();
+
+        let limits = InventoryLimits { max_slots: None, max_weight: Some(5) };
+        craft("craft_test_arrows", &limits, &mut player, &mut events);
+        assert_eq!(items::count_of(&player.inventory, "Craft Test Shaft"), 2);
+        assert!(matches!(&events[..], [GameEvent::InventoryFull(_)]));
+
+        // The shaft used up makes just enough room
+        let limits = InventoryLimits { max_slots: None, max_weight: Some(6) };
+        craft("craft_test_arrows", &limits, &mut player, &mut events);
+        assert_eq!(items::count_of(&player.inventory, "Craft Test Arrow"), 5);
+    }
+
+    #[test]
+    fn unknown_recipes_have_to_be_learned_first() {
+        register("craft_test_charm", json!({
+            "name": "Craft Test Charm",
+            "room": "workshop",
+            "output": item("Craft Test Charm", 1)
+        }));
+        let mut player = Player::new();
+        let mut events = Vec::new();
+
+        craft("craft_test_charm", &InventoryLimits::default(), &mut player, &mut events);
+        assert!(matches!(&events[..], [GameEvent::UnknownRecipe(_)]));
+
+        assert!(learn("craft_test_charm", &mut player, &mut events));
+        assert!(!learn("craft_test_charm", &mut player, &mut events));
+
+        events.clear();
+        craft("craft_test_charm", &InventoryLimits::default(), &mut player, &mut events);
+        assert!(matches!(&events[..], [GameEvent::CraftNeedsRoom { room, .. }] if room == "workshop"));
+
+        player.current_room = "workshop".to_string();
+        craft("craft_test_charm", &InventoryLimits::default(), &mut player, &mut events);
+        assert_eq!(items::count_of(&player.inventory, "Craft Test Charm"), 1);
+    }
+}
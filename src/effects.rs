@@ -0,0 +1,222 @@
+use serde::{Serialize, Deserialize};
+use rand::Rng;
+use crate::events::GameEvent;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectKind {
+    /// Loses `power` HP a turn. Stacks: each new dose adds its power.
+    Poison,
+    /// Loses `power` HP a turn. Doesn't stack; the stronger burn wins.
+    Burn,
+    /// Skips the next `turns` actions
+    Stun,
+    /// Regains `power` HP a turn. Doesn't stack; the stronger one wins.
+    Regen,
+}
+
+impl EffectKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EffectKind::Poison => "poisoned",
+            EffectKind::Burn => "burning",
+            EffectKind::Stun => "stunned",
+            EffectKind::Regen => "regenerating",
+        }
+    }
+}
+
+/// An effect on the player or an enemy, counting down each turn
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct StatusEffect {
+    pub kind: EffectKind,
+    /// Turns left
+    pub turns: u32,
+    /// HP lost or regained each turn; unused by stun
+    #[serde(default)]
+    pub power: i32,
+}
+
+/// An effect with a chance of landing, as carried by attacks and items
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Affliction {
+    #[serde(flatten)]
+    pub effect: StatusEffect,
+    #[serde(default = "always")]
+    pub chance: f64,
+}
+
+fn always() -> f64 {
+    1.0
+}
+
+/// Who an effect event is about: None for the player, or the enemy's name
+pub type Target = Option<String>;
+
+/// Adds an effect, merging it with one of the same kind. Either way the longer duration is kept.
+pub fn apply(effects: &mut Vec<StatusEffect>, effect: StatusEffect, target: &Target, events: &mut Vec<GameEvent>) {
+    match effects.iter_mut().find(|e| e.kind == effect.kind) {
+        Some(existing) => {
+            existing.turns = existing.turns.max(effect.turns);
+            existing.power = match effect.kind {
+                EffectKind::Poison => existing.power + effect.power,
+                _ => existing.power.max(effect.power),
+            };
+        }
+        None => effects.push(effect),
+    }
+    events.push(GameEvent::EffectApplied { target: target.clone(), effect: effect.kind, turns: effect.turns });
+}
+
+/// Rolls each affliction's chance and applies the ones that land
+pub fn afflict(
+    afflictions: &[Affliction],
+    effects: &mut Vec<StatusEffect>,
+    target: &Target,
+    rng: &mut impl Rng,
+    events: &mut Vec<GameEvent>,
+) {
+    for affliction in afflictions {
+        if rng.gen_bool(affliction.chance.clamp(0.0, 1.0)) {
+            apply(effects, affliction.effect, target, events);
+        }
+    }
+}
+
+/// Removes every effect of the given kinds
+pub fn cure(effects: &mut Vec<StatusEffect>, kinds: &[EffectKind], target: &Target, events: &mut Vec<GameEvent>) {
+    effects.retain(|effect| {
+        let cured = kinds.contains(&effect.kind);
+        if cured {
+            events.push(GameEvent::EffectEnded { target: target.clone(), effect: effect.kind });
+        }
+        !cured
+    });
+}
+
+/// Runs one turn of every effect: damage and healing land, and all of them count down.
+/// Unless `lethal`, damage stops at 1 HP.
+pub fn tick(
+    effects: &mut Vec<StatusEffect>,
+    health: &mut i32,
+    max_health: i32,
+    lethal: bool,
+    target: &Target,
+    events: &mut Vec<GameEvent>,
+) {
+    for effect in effects.iter_mut() {
+        let change = match effect.kind {
+            EffectKind::Stun => 0,
+            EffectKind::Regen => effect.power.min(max_health - *health).max(0),
+            _ if lethal => -effect.power,
+            _ => -effect.power.min(*health - 1).max(0),
+        };
+        *health += change;
+        effect.turns = effect.turns.saturating_sub(1);
+        if change != 0 {
+            events.push(GameEvent::EffectTick { target: target.clone(), effect: effect.kind, change, health: *health });
+        }
+    }
+    expire(effects, target, events);
+}
+
+/// Whether a stun costs this turn. Check it before the turn's `tick`, which counts the stun down.
+pub fn is_stunned(effects: &[StatusEffect], target: &Target, events: &mut Vec<GameEvent>) -> bool {
+    let stunned = effects.iter().any(|e| e.kind == EffectKind::Stun && e.turns > 0);
+    if stunned {
+        events.push(GameEvent::Stunned { target: target.clone() });
+    }
+    stunned
+}
+
+fn expire(effects: &mut Vec<StatusEffect>, target: &Target, events: &mut Vec<GameEvent>) {
+    effects.retain(|effect| {
+        if effect.turns == 0 {
+            events.push(GameEvent::EffectEnded { target: target.clone(), effect: effect.kind });
+        }
+        effect.turns > 0
+    });
+}
+
+/// Kind and turns left of each effect, for display
+pub fn summary(effects: &[StatusEffect]) -> Vec<(EffectKind, u32)> {
+    effects.iter().map(|e| (e.kind, e.turns)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn effect(kind: EffectKind, turns: u32, power: i32) -> StatusEffect {
+        StatusEffect { kind, turns, power }
+    }
+
+    #[test]
+    fn poison_stacks_its_power() {
+        let mut effects = Vec::new();
+        let mut events = Vec::new();
+
+        apply(&mut effects, effect(EffectKind::Poison, 3, 2), &None, &mut events);
+        apply(&mut effects, effect(EffectKind::Poison, 2, 3), &None, &mut events);
+
+        assert_eq!(effects.len(), 1);
+        assert_eq!((effects[0].turns, effects[0].power), (3, 5));
+    }
+
+    #[test]
+    fn repeats_refresh_to_the_stronger_and_longer_effect() {
+        let mut effects = Vec::new();
+        let mut events = Vec::new();
+
+        apply(&mut effects, effect(EffectKind::Burn, 2, 4), &None, &mut events);
+        apply(&mut effects, effect(EffectKind::Burn, 5, 1), &None, &mut events);
+        assert_eq!((effects[0].turns, effects[0].power), (5, 4));
+
+        apply(&mut effects, effect(EffectKind::Regen, 1, 3), &None, &mut events);
+        assert_eq!(effects.len(), 2);
+    }
+
+    #[test]
+    fn effects_expire_after_their_turns() {
+        let mut effects = vec![effect(EffectKind::Poison, 2, 5), effect(EffectKind::Stun, 1, 0)];
+        let mut health = 50;
+        let mut events = Vec::new();
+
+        assert!(is_stunned(&effects, &None, &mut events));
+        tick(&mut effects, &mut health, 100, true, &None, &mut events);
+        assert_eq!(health, 45);
+        assert!(!is_stunned(&effects, &None, &mut events));
+
+        tick(&mut effects, &mut health, 100, true, &None, &mut events);
+        assert_eq!(health, 40);
+        assert!(effects.is_empty());
+        let ended = events.iter().filter(|e| matches!(e, GameEvent::EffectEnded { .. })).count();
+        assert_eq!(ended, 2);
+    }
+
+    #[test]
+    fn ticks_outside_a_fight_never_kill() {
+        let mut effects = vec![effect(EffectKind::Burn, 3, 10), effect(EffectKind::Regen, 3, 0)];
+        let mut health = 4;
+        let mut events = Vec::new();
+
+        tick(&mut effects, &mut health, 100, false, &None, &mut events);
+        assert_eq!(health, 1);
+
+        // Regeneration stops at full health
+        let mut effects = vec![effect(EffectKind::Regen, 3, 10)];
+        let mut health = 95;
+        tick(&mut effects, &mut health, 100, false, &None, &mut events);
+        assert_eq!(health, 100);
+    }
+
+    #[test]
+    fn cures_remove_only_their_kinds() {
+        let mut effects = vec![effect(EffectKind::Poison, 2, 5), effect(EffectKind::Burn, 2, 5)];
+        let mut events = Vec::new();
+
+        cure(&mut effects, &[EffectKind::Poison], &None, &mut events);
+
+        assert_eq!(summary(&effects), [(EffectKind::Burn, 2)]);
+    }
+}
@@ -1,8 +1,13 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
+use std::path::Path;
 use once_cell::sync::Lazy;
 use std::sync::RwLock;
+use rand::Rng;
+use crate::effects::{Affliction, StatusEffect};
+use crate::items::{self, Item};
+use crate::error::GameResult;
+use crate::save_load::read_asset;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Enemy {
@@ -11,6 +16,102 @@ pub struct Enemy {
     pub health: i32,
     pub attack: i32,
     pub xp_reward: i32,
+    #[serde(default)]
+    pub loot: Vec<LootEntry>,
+    /// Gold dropped on defeat, as [min, max]
+    #[serde(default)]
+    pub gold: (u32, u32),
+    /// Effects its hits may put on the player
+    #[serde(default)]
+    pub inflicts: Vec<Affliction>,
+    /// Effects currently on it
+    #[serde(default)]
+    pub effects: Vec<StatusEffect>,
+    /// Special moves picked by weight alongside the plain attack
+    #[serde(default)]
+    pub abilities: Vec<Ability>,
+    /// Weight of the plain attack when picking a move
+    #[serde(default = "default_weight")]
+    pub attack_weight: u32,
+}
+
+/// A move an enemy can pick instead of its plain attack
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Ability {
+    pub name: String,
+    /// Shown when the ability is used
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(flatten)]
+    pub effect: AbilityEffect,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    /// Only picked at or below this percentage of health
+    #[serde(default)]
+    pub below_health: Option<i32>,
+    /// Times it can be used per fight
+    #[serde(default)]
+    pub uses: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AbilityEffect {
+    /// An attack at `percent` of the enemy's attack, with extra effects
+    Strike {
+        #[serde(default = "default_percent")]
+        percent: i32,
+        #[serde(default)]
+        inflicts: Vec<Affliction>,
+    },
+    /// Restores HP, up to its health at the start of the fight
+    Heal { amount: i32 },
+    /// Halves the player's next hit
+    Defend,
+    /// Calls another enemy, which attacks once this one is gone
+    Summon { enemy: String },
+    /// Leaves the fight, and the room
+    Flee,
+}
+
+fn default_weight() -> u32 {
+    10
+}
+
+fn default_percent() -> i32 {
+    100
+}
+
+/// One weighted outcome of an enemy's loot roll; no item means nothing drops
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LootEntry {
+    pub weight: u32,
+    #[serde(default, deserialize_with = "items::deserialize_optional_item")]
+    pub item: Option<Item>,
+}
+
+impl Enemy {
+    /// Picks one entry from the loot table by weight
+    pub fn roll_loot(&self, rng: &mut impl Rng) -> Option<Item> {
+        let total: u32 = self.loot.iter().map(|e| e.weight).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0..total);
+        for entry in &self.loot {
+            if roll < entry.weight {
+                return entry.item.clone();
+            }
+            roll -= entry.weight;
+        }
+        None
+    }
+
+    pub fn roll_gold(&self, rng: &mut impl Rng) -> u32 {
+        let (min, max) = self.gold;
+        if max == 0 { 0 } else { rng.gen_range(min.min(max)..=max) }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -22,15 +123,13 @@ pub struct EnemyData {
 pub static ENEMY_LIST: Lazy<RwLock<HashMap<String, Enemy>>> = Lazy::new(|| RwLock::new(HashMap::new()));
 
 /// Loads enemies from JSON into global memory
-pub fn load_enemies(path: &str) -> HashMap<String, Enemy> {
-    let data = fs::read_to_string(path).expect("Failed to read enemies.json");
-    let enemy_data: EnemyData = serde_json::from_str(&data).expect("Failed to parse enemies.json");
+pub fn load_enemies(path: impl AsRef<Path>) -> GameResult<HashMap<String, Enemy>> {
+    let enemy_data: EnemyData = read_asset(path)?;
 
     let mut global_enemies = ENEMY_LIST.write().unwrap();
     *global_enemies = enemy_data.enemies.clone();
 
-    println!("Loaded {} enemies from {}", global_enemies.len(), path);
-    enemy_data.enemies
+    Ok(enemy_data.enemies)
 }
 
 /// Get a cloned enemy by name
@@ -38,3 +137,50 @@ pub fn get_enemy_by_name(name: &str) -> Option<Enemy> {
     let enemies = ENEMY_LIST.read().unwrap();
     enemies.get(name).cloned()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+    use serde_json::json;
+
+    fn enemy_with_loot(loot: serde_json::Value) -> Enemy {
+        serde_json::from_value(json!({
+            "name": "Goblin",
+            "description": "",
+            "health": 20,
+            "attack": 4,
+            "xp_reward": 10,
+            "loot": loot
+        }))
+        .unwrap()
+    }
+
+    fn herb() -> serde_json::Value {
+        json!({ "name": "Healing Herb", "item_type": "Healing", "power": 20, "usable_on": null })
+    }
+
+    #[test]
+    fn loot_follows_the_weights() {
+        let enemy = enemy_with_loot(json!([{ "weight": 3 }, { "weight": 1, "item": herb() }]));
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+
+        let drops = (0..4000).filter(|_| enemy.roll_loot(&mut rng).is_some()).count();
+        assert!((800..1200).contains(&drops), "{} drops", drops);
+    }
+
+    #[test]
+    fn no_loot_table_drops_nothing() {
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+        assert!(enemy_with_loot(json!([])).roll_loot(&mut rng).is_none());
+        assert!(enemy_with_loot(json!([{ "weight": 0, "item": herb() }])).roll_loot(&mut rng).is_none());
+    }
+
+    #[test]
+    fn a_sure_drop_always_drops() {
+        let enemy = enemy_with_loot(json!([{ "weight": 1, "item": herb() }]));
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+        assert_eq!(enemy.roll_loot(&mut rng).unwrap().name, "Healing Herb");
+    }
+}
@@ -0,0 +1,604 @@
+use std::path::PathBuf;
+use std::time::Instant;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+
+use crate::campaign::Campaign;
+use crate::combat::{CombatAction, CombatOutcome, CombatState};
+use crate::commands::Command;
+use crate::crafting;
+use crate::effects;
+use crate::events::GameEvent;
+use crate::error::{GameError, GameResult};
+use crate::enemies::get_enemy_by_name;
+use crate::items;
+use crate::npcs::{self, Conversation};
+use crate::player::Player;
+use crate::quests::{self, ObjectiveKind};
+use crate::shops;
+use crate::save_load::{
+    list_saves, load_game, load_world, now_secs, save_game, slot_path, RngState, SaveMeta, DEFAULT_SLOT,
+};
+use crate::triggers::{self, TriggerEvent};
+use crate::world::{self, World};
+
+/// Settings for starting a new game
+pub struct EngineConfig {
+    /// Directory holding the save slots
+    pub save_dir: PathBuf,
+    /// All randomness comes from this, so the same seed and inputs replay identically
+    pub seed: u64,
+    /// Level id or manifest position; defaults to the campaign's start level
+    pub start_level: Option<String>,
+    pub player_name: Option<String>,
+}
+
+/// Headless game engine: feed it commands, get back events to present.
+pub struct Engine {
+    pub player: Player,
+    pub world: World,
+    campaign: Campaign,
+    save_dir: PathBuf,
+    combat: Option<CombatState>,
+    /// Conversation the player is in, if any
+    dialogue: Option<Conversation>,
+    defeated: bool,
+    seed: u64,
+    rng: ChaCha12Rng,
+    /// Playtime carried over from the loaded save
+    playtime_base: u64,
+    session_start: Instant,
+}
+
+impl Engine {
+    /// Creates a new game at the configured (or the campaign's) starting level
+    pub fn new(campaign: Campaign, config: EngineConfig) -> GameResult<Self> {
+        let start = match &config.start_level {
+            Some(key) => campaign
+                .resolve_level(key)
+                .ok_or_else(|| GameError::MissingLevel(key.clone()))?,
+            None => campaign
+                .start_index()
+                .ok_or_else(|| GameError::MissingLevel(campaign.start.clone()))?,
+        };
+        let level = &campaign.levels[start];
+
+        let mut player = Player::new();
+        if let Some(name) = config.player_name {
+            player.name = name;
+        }
+        player.current_level = start;
+        player.current_room = level.start_room.clone();
+        let world = load_world(campaign.level_path(level))?;
+
+        Ok(Self {
+            player,
+            world,
+            campaign,
+            save_dir: config.save_dir,
+            combat: None,
+            dialogue: None,
+            defeated: false,
+            seed: config.seed,
+            rng: ChaCha12Rng::seed_from_u64(config.seed),
+            playtime_base: 0,
+            session_start: Instant::now(),
+        })
+    }
+
+    /// Opens the game: describes the first scene and hands out the level's quests
+    pub fn start(&mut self) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        let title = match self.campaign.level(self.player.current_level) {
+            Some(level) => level.title.clone(),
+            None => "The Realm of Aether".to_string(),
+        };
+
+        events.push(GameEvent::LevelEntered { title });
+        events.push(GameEvent::Welcome { name: self.player.name.clone() });
+        world::look(&self.player, &self.world, &mut events);
+        self.start_level_quests(&mut events);
+        events
+    }
+
+    pub fn in_combat(&self) -> bool {
+        self.combat.is_some()
+    }
+
+    /// The fight in progress, if any
+    pub fn combat(&self) -> Option<&CombatState> {
+        self.combat.as_ref()
+    }
+
+    /// Seed this game's randomness was started from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Total seconds played, including time from the loaded save
+    pub fn playtime_secs(&self) -> u64 {
+        self.playtime_base + self.session_start.elapsed().as_secs()
+    }
+
+    /// True once the player has fallen; only `load` and `quit` do anything until then
+    pub fn is_defeated(&self) -> bool {
+        self.defeated
+    }
+
+    /// Advances the game by one command
+    pub fn apply(&mut self, command: Command) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+
+        if self.defeated {
+            self.apply_defeated(command, &mut events);
+            return events;
+        }
+
+        // Loading replaces the whole game and quitting ends it, so neither finishes a turn
+        let ends_turn = !matches!(command, Command::Load(_) | Command::Quit);
+        let was_completed = self.level_completed();
+        let room_before = self.player.current_room.clone();
+
+        if self.combat.is_some() {
+            self.apply_combat(command, &mut events);
+        } else {
+            self.apply_explore(command, &mut events);
+        }
+
+        if ends_turn {
+            self.finish_turn(was_completed, &room_before, &mut events);
+        }
+        events
+    }
+
+    fn apply_explore(&mut self, command: Command, events: &mut Vec<GameEvent>) {
+        // Checking on yourself keeps the conversation going; anything else walks away
+        let dialogue = self.dialogue.take();
+        if matches!(command, Command::Help | Command::Status | Command::Inventory | Command::Journal) {
+            self.dialogue = dialogue.clone();
+        }
+
+        match command {
+            Command::Help => events.push(GameEvent::Help),
+            Command::Status => events.push(GameEvent::Status(Box::new(self.player.clone()))),
+            Command::Go(dir) => {
+                let room_before = self.player.current_room.clone();
+                match world::move_player(dir, &mut self.player, &mut self.world, events) {
+                    Ok(combat) => self.combat = combat,
+                    Err(e) => events.push(GameEvent::Error(e.to_string())),
+                }
+                // Each move is a turn for lingering effects, though they can't kill outside a fight
+                if self.player.current_room != room_before {
+                    let player = &mut self.player;
+                    effects::tick(&mut player.effects, &mut player.health, player.max_health, false, &None, events);
+                }
+                self.push_combat_status(events);
+            }
+            Command::Look => world::look(&self.player, &self.world, events),
+            Command::Examine(target) => world::examine(&target, &self.player, &self.world, events),
+
+            Command::Take(item, count) => {
+                let limits = &self.campaign.inventory;
+                world::take_item(&item, count, limits, &mut self.player, &mut self.world, events);
+            }
+
+            Command::Drop(item, count) => {
+                world::drop_item(&item, count, &mut self.player, &mut self.world, events);
+            }
+            Command::TakeFrom { item, count, container } => {
+                let limits = &self.campaign.inventory;
+                world::take_from_container(
+                    &item, count, &container, limits, &mut self.player, &mut self.world, events,
+                );
+            }
+            Command::Put { item, count, container } => {
+                world::put_item(&item, count, &container, &mut self.player, &mut self.world, events);
+            }
+            Command::Open(container) => {
+                world::open_container(&container, &mut self.player, &mut self.world, events);
+            }
+            Command::Search(target) => world::search(&target, &mut self.player, &mut self.world, events),
+
+            Command::Use(item, count) => {
+                for _ in 0..count.unwrap_or(1) {
+                    let before = items::count_of(&self.player.inventory, &item);
+                    let completed = world::use_item(&item, &mut self.player, &mut self.world, events);
+                    if completed {
+                        self.complete_current_level();
+                        break;
+                    }
+
+                    // Repeat only while each use actually spends an item
+                    let after = items::count_of(&self.player.inventory, &item);
+                    if after == 0 || after == before {
+                        break;
+                    }
+                }
+            }
+
+            Command::Inventory => events.push(GameEvent::Inventory(self.player.inventory.clone())),
+            Command::Journal => events.push(GameEvent::Journal(quests::journal(&self.player))),
+            Command::Equip(item) => self.player.equip(&item, events),
+            Command::Unequip(target) => self.player.unequip(&target, events),
+
+            Command::Talk(target) => {
+                self.dialogue = npcs::talk(&target, &mut self.player, &mut self.world, events);
+            }
+            Command::Choose(number) => match dialogue {
+                Some(conversation) => {
+                    self.dialogue =
+                        npcs::choose(&conversation, number, &mut self.player, &mut self.world, events);
+                }
+                None => events.push(GameEvent::NotTalking),
+            },
+            Command::Bye => match dialogue.and_then(|conversation| npcs::get_npc(&conversation.npc)) {
+                Some(npc) => events.push(GameEvent::DialogueEnded { npc: npc.name }),
+                None => events.push(GameEvent::NotTalking),
+            },
+
+            Command::Craft(recipe) => crafting::craft(&recipe, &self.campaign.inventory, &mut self.player, events),
+            Command::Recipes => events.push(GameEvent::Recipes(crafting::recipes(&self.player))),
+
+            Command::List => shops::list(&self.player, &mut self.world, events),
+            Command::Buy(item, count) => {
+                let limits = &self.campaign.inventory;
+                shops::buy(&item, count, limits, &mut self.player, &mut self.world, events);
+            }
+            Command::Sell(item, count) => shops::sell(&item, count, &mut self.player, &mut self.world, events),
+
+            Command::Save(slot) => self.save(&slot, events),
+            Command::Load(slot) => self.load(&slot, events),
+            Command::Saves => events.push(GameEvent::SaveList(list_saves(&self.save_dir))),
+
+            Command::Quit => events.push(GameEvent::Quit),
+
+            // Combat actions outside of a fight
+            Command::Attack | Command::Heal | Command::Defend | Command::Run => {
+                events.push(GameEvent::NotInCombat);
+            }
+
+            Command::Unknown(cmd) => events.push(GameEvent::Unknown(cmd)),
+        }
+    }
+
+    // Follow-ups that any command can cause, including ones set off by triggers
+    fn finish_turn(&mut self, was_completed: bool, room_before: &str, events: &mut Vec<GameEvent>) {
+        if self.defeated {
+            return;
+        }
+
+        if self.level_completed() {
+            if !was_completed {
+                if let Some(level) = self.campaign.level(self.player.current_level) {
+                    events.push(GameEvent::LevelCompleted { text: level.completion_text.clone() });
+                }
+            }
+            self.handle_level_progression(events);
+        }
+
+        // An enemy spawned into the player's room attacks right away
+        if self.combat.is_none() {
+            self.start_fight_here(room_before, events);
+        }
+    }
+
+    fn start_fight_here(&mut self, room_before: &str, events: &mut Vec<GameEvent>) {
+        let Some(room) = self.world.rooms.get(&self.player.current_room) else { return };
+        let Some(enemy_name) = &room.enemy else { return };
+        let Some(enemy) = get_enemy_by_name(enemy_name) else {
+            events.push(GameEvent::Error(GameError::MissingEnemy(enemy_name.clone()).to_string()));
+            return;
+        };
+
+        // Fleeing leads back where the player came from, or out of the first exit
+        let retreat = if room_before != room.id {
+            room_before.to_string()
+        } else {
+            room.exits.values().min().cloned().unwrap_or_else(|| room.id.clone())
+        };
+        self.dialogue = None;
+        self.combat = Some(CombatState::new(enemy, &room.id, &retreat, events));
+        self.push_combat_status(events);
+    }
+
+    // === SAVING ===
+
+    // Empty slot names fall back to the quicksave slot
+    fn slot_name(slot: &str) -> &str {
+        if slot.is_empty() { DEFAULT_SLOT } else { slot }
+    }
+
+    fn save(&self, slot: &str, events: &mut Vec<GameEvent>) {
+        let slot = Self::slot_name(slot);
+        let Some(path) = slot_path(&self.save_dir, slot) else {
+            events.push(GameEvent::InvalidSlotName(slot.to_string()));
+            return;
+        };
+
+        let meta = SaveMeta {
+            saved_at: now_secs(),
+            level: self
+                .campaign
+                .level(self.player.current_level)
+                .map(|level| level.title.clone())
+                .unwrap_or_default(),
+            room: self.player.current_room.clone(),
+            player_level: self.player.level,
+            playtime_secs: self.playtime_secs(),
+        };
+
+        let rng = RngState::capture(self.seed, &self.rng);
+        match save_game(&self.player, &self.world, self.combat.as_ref(), meta, rng, &path) {
+            Ok(()) => events.push(GameEvent::Saved(slot.to_string())),
+            Err(e) => events.push(GameEvent::SaveFailed { slot: slot.to_string(), reason: e.to_string() }),
+        }
+    }
+
+    fn load(&mut self, slot: &str, events: &mut Vec<GameEvent>) {
+        let slot = Self::slot_name(slot);
+        let Some(path) = slot_path(&self.save_dir, slot) else {
+            events.push(GameEvent::InvalidSlotName(slot.to_string()));
+            return;
+        };
+        if !path.exists() {
+            events.push(GameEvent::NoSuchSave(slot.to_string()));
+            return;
+        }
+
+        let (meta, rng) = match load_game(&mut self.player, &mut self.world, &mut self.combat, &path) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                events.push(GameEvent::LoadFailed { slot: slot.to_string(), reason: e.to_string() });
+                return;
+            }
+        };
+        // Saves from before triggers existed lack them; the level still defines them
+        if let Some(level) = self.campaign.level(self.player.current_level) {
+            if let Ok(asset) = load_world(self.campaign.level_path(level)) {
+                world::restore_triggers(&mut self.world, asset);
+            }
+        }
+        // Older saves didn't keep the stream; restart it from this game's seed
+        let rng = rng.unwrap_or(RngState { seed: self.seed, word_pos: 0 });
+        self.seed = rng.seed;
+        self.rng = rng.restore();
+        self.playtime_base = meta.playtime_secs;
+        self.session_start = Instant::now();
+        self.defeated = false;
+        self.dialogue = None;
+        events.push(GameEvent::Loaded(slot.to_string()));
+        self.push_combat_status(events);
+    }
+
+    // === COMBAT ===
+
+    fn apply_combat(&mut self, command: Command, events: &mut Vec<GameEvent>) {
+        match command {
+            Command::Help => events.push(GameEvent::Help),
+            Command::Status => events.push(GameEvent::Status(Box::new(self.player.clone()))),
+            Command::Inventory => events.push(GameEvent::Inventory(self.player.inventory.clone())),
+            Command::Journal => events.push(GameEvent::Journal(quests::journal(&self.player))),
+            Command::Recipes => events.push(GameEvent::Recipes(crafting::recipes(&self.player))),
+            Command::Examine(target) => self.examine_in_combat(&target, events),
+            Command::Save(slot) => self.save(&slot, events),
+            Command::Load(slot) => {
+                self.load(&slot, events);
+                return;
+            }
+            Command::Saves => events.push(GameEvent::SaveList(list_saves(&self.save_dir))),
+            Command::Quit => {
+                events.push(GameEvent::Quit);
+                return;
+            }
+            command => {
+                // Invalid input never gives the enemy a free hit
+                let Some(action) = CombatAction::from_command(&command) else {
+                    events.push(GameEvent::InvalidCombatAction);
+                    self.push_combat_status(events);
+                    return;
+                };
+                let Some(combat) = self.combat.as_mut() else { return };
+
+                match combat.advance(action, &mut self.player, &mut self.rng, events) {
+                    CombatOutcome::Ongoing => {}
+                    CombatOutcome::EnemyDefeated => {
+                        let enemy = combat.enemy.name.clone();
+                        let room = combat.room.clone();
+                        let summoned = combat.summoned.take();
+                        world::clear_enemy(&combat.room, &mut self.world);
+                        if let Some(item) = combat.enemy.roll_loot(&mut self.rng) {
+                            events.push(GameEvent::LootDropped {
+                                enemy: combat.enemy.name.clone(),
+                                item: item.name.clone(),
+                            });
+                            world::place_item(&combat.room, item, &mut self.world);
+                        }
+                        let gold = combat.enemy.roll_gold(&mut self.rng);
+                        if gold > 0 {
+                            self.player.gold += gold;
+                            events.push(GameEvent::GoldLooted { enemy: enemy.clone(), amount: gold });
+                        }
+                        self.combat = None;
+                        quests::record(ObjectiveKind::Kill, &enemy, &mut self.player, events);
+                        triggers::fire(
+                            TriggerEvent::OnEnemyDefeated,
+                            Some(&enemy),
+                            &mut self.player,
+                            &mut self.world,
+                            events,
+                        );
+                        self.place_summoned(&room, summoned);
+                    }
+                    CombatOutcome::EnemyFled => {
+                        let room = combat.room.clone();
+                        let summoned = combat.summoned.take();
+                        world::clear_enemy(&room, &mut self.world);
+                        self.combat = None;
+                        self.place_summoned(&room, summoned);
+                    }
+                    CombatOutcome::Fled => self.combat = None,
+                    CombatOutcome::PlayerDefeated => {
+                        self.combat = None;
+                        self.defeated = true;
+                    }
+                }
+            }
+        }
+
+        self.push_combat_status(events);
+    }
+
+    // A summoned enemy outlasts its summoner and attacks once the turn ends
+    fn place_summoned(&mut self, room: &str, summoned: Option<String>) {
+        let Some(enemy) = summoned else { return };
+        if let Some(room) = self.world.rooms.get_mut(room) {
+            room.enemy = Some(enemy);
+        }
+    }
+
+    // Looking things over is free; the foe shows its current health
+    fn examine_in_combat(&self, target: &str, events: &mut Vec<GameEvent>) {
+        match &self.combat {
+            Some(combat) if combat.enemy.name.eq_ignore_ascii_case(target) => {
+                events.push(GameEvent::EnemyExamined {
+                    name: combat.enemy.name.clone(),
+                    description: combat.enemy.description.clone(),
+                    health: combat.enemy.health,
+                    attack: combat.enemy.attack,
+                });
+            }
+            _ => world::examine(target, &self.player, &self.world, events),
+        }
+    }
+
+    fn apply_defeated(&mut self, command: Command, events: &mut Vec<GameEvent>) {
+        match command {
+            Command::Load(slot) => self.load(&slot, events),
+            Command::Saves => events.push(GameEvent::SaveList(list_saves(&self.save_dir))),
+            Command::Quit => events.push(GameEvent::Quit),
+            Command::Help => events.push(GameEvent::Help),
+            Command::Status => events.push(GameEvent::Status(Box::new(self.player.clone()))),
+            _ => events.push(GameEvent::StillDefeated),
+        }
+    }
+
+    fn push_combat_status(&self, events: &mut Vec<GameEvent>) {
+        if let Some(combat) = &self.combat {
+            events.push(GameEvent::CombatStatus {
+                health: self.player.health,
+                effects: effects::summary(&self.player.effects),
+                enemy: combat.enemy.name.clone(),
+                enemy_health: combat.enemy.health,
+                enemy_effects: effects::summary(&combat.enemy.effects),
+            });
+        }
+    }
+
+    // === LEVEL PROGRESSION ===
+
+    fn level_completed(&self) -> bool {
+        self.campaign
+            .level(self.player.current_level)
+            .and_then(|level| level.completion_flag.as_ref())
+            .is_some_and(|flag| self.player.flags.contains(flag))
+    }
+
+    // Mark the current level as completed; its text is reported when the turn finishes
+    fn complete_current_level(&mut self) {
+        let Some(level) = self.campaign.level(self.player.current_level) else { return };
+        let Some(flag) = &level.completion_flag else { return };
+
+        if !self.player.flags.contains(flag) {
+            self.player.flags.push(flag.clone());
+        }
+    }
+
+    // Follow the manifest to the next level once the current one is completed
+    fn handle_level_progression(&mut self, events: &mut Vec<GameEvent>) {
+        let Some(level) = self.campaign.level(self.player.current_level) else { return };
+        let Some(next_id) = &level.next else { return };
+        let Some(next_index) = self.campaign.level_index(next_id) else {
+            events.push(GameEvent::Error(GameError::MissingLevel(next_id.clone()).to_string()));
+            return;
+        };
+        let next = &self.campaign.levels[next_index];
+
+        // Stay in the current level if the next one can't be loaded
+        let world = match load_world(self.campaign.level_path(next)) {
+            Ok(world) => world,
+            Err(e) => {
+                events.push(GameEvent::Error(e.to_string()));
+                return;
+            }
+        };
+
+        events.push(GameEvent::LevelEntered { title: next.title.clone() });
+        self.world = world;
+        self.player.current_room = next.start_room.clone();
+        self.player.current_level = next_index;
+
+        world::look(&self.player, &self.world, events);
+        self.start_level_quests(events);
+    }
+
+    fn start_level_quests(&mut self, events: &mut Vec<GameEvent>) {
+        let Some(level) = self.campaign.level(self.player.current_level) else { return };
+        for id in &level.quests {
+            quests::start(id, &mut self.player, events);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::campaign::load_campaign;
+    use crate::enemies::{Enemy, ENEMY_LIST};
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn a_summoned_enemy_takes_over_the_room() {
+        // The registry is shared by every test, so only add entries no other test uses
+        for enemy in [
+            json!({
+                "name": "Summon Test Shaman", "description": "", "health": 20, "attack": 1, "xp_reward": 1,
+                "attack_weight": 0,
+                "abilities": [{ "name": "Call", "type": "summon", "enemy": "Summon Test Imp", "uses": 1 }]
+            }),
+            json!({ "name": "Summon Test Imp", "description": "", "health": 10, "attack": 1, "xp_reward": 1 }),
+        ] {
+            let enemy: Enemy = serde_json::from_value(enemy).unwrap();
+            ENEMY_LIST.write().unwrap().insert(enemy.name.clone(), enemy);
+        }
+
+        let dir = std::env::temp_dir().join(format!("shards_of_aether_summon_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("campaign.json"),
+            json!({ "start": "one", "levels": [{ "id": "one", "file": "one.json", "title": "One", "start_room": "hall" }] })
+                .to_string(),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("one.json"),
+            json!({ "rooms": { "hall": { "id": "hall", "description": "", "enemy": "Summon Test Shaman" } } }).to_string(),
+        )
+        .unwrap();
+        let campaign = load_campaign(dir.join("campaign.json")).unwrap();
+        let config = EngineConfig { save_dir: dir.join("saves"), seed: 8, start_level: None, player_name: None };
+        let mut engine = Engine::new(campaign, config).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        engine.apply(Command::Look);
+        let mut events = Vec::new();
+        while engine.combat().is_some_and(|c| c.enemy.name == "Summon Test Shaman") {
+            events.extend(engine.apply(Command::Attack));
+        }
+
+        assert!(events.iter().any(|e| matches!(e, GameEvent::EnemySummoned { .. })));
+        assert_eq!(engine.world.rooms["hall"].enemy.as_deref(), Some("Summon Test Imp"));
+        assert_eq!(engine.combat().map(|c| c.enemy.name.as_str()), Some("Summon Test Imp"));
+    }
+}
@@ -0,0 +1,87 @@
+use serde::{Serialize, Deserialize};
+use crate::items::{Item, ItemType};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EquipSlot {
+    Weapon,
+    Armor,
+    Trinket,
+}
+
+impl EquipSlot {
+    pub const ALL: [EquipSlot; 3] = [EquipSlot::Weapon, EquipSlot::Armor, EquipSlot::Trinket];
+
+    /// Slot an item goes into, if it can be equipped at all
+    pub fn for_item(item: &Item) -> Option<Self> {
+        match item.item_type {
+            ItemType::Weapon => Some(EquipSlot::Weapon),
+            ItemType::Armor => Some(EquipSlot::Armor),
+            ItemType::Trinket => Some(EquipSlot::Trinket),
+            _ => None,
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        EquipSlot::ALL
+            .into_iter()
+            .find(|slot| slot.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EquipSlot::Weapon => "weapon",
+            EquipSlot::Armor => "armor",
+            EquipSlot::Trinket => "trinket",
+        }
+    }
+}
+
+/// Gear the player is wearing. Weapons and trinkets add their power to
+/// attack, armor adds its power to defense.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Equipment {
+    #[serde(default)]
+    pub weapon: Option<Item>,
+    #[serde(default)]
+    pub armor: Option<Item>,
+    #[serde(default)]
+    pub trinket: Option<Item>,
+}
+
+impl Equipment {
+    pub fn get(&self, slot: EquipSlot) -> Option<&Item> {
+        match slot {
+            EquipSlot::Weapon => self.weapon.as_ref(),
+            EquipSlot::Armor => self.armor.as_ref(),
+            EquipSlot::Trinket => self.trinket.as_ref(),
+        }
+    }
+
+    pub fn slot_mut(&mut self, slot: EquipSlot) -> &mut Option<Item> {
+        match slot {
+            EquipSlot::Weapon => &mut self.weapon,
+            EquipSlot::Armor => &mut self.armor,
+            EquipSlot::Trinket => &mut self.trinket,
+        }
+    }
+
+    /// Slot currently holding an item with this name
+    pub fn find(&self, item_name: &str) -> Option<EquipSlot> {
+        EquipSlot::ALL.into_iter().find(|&slot| {
+            self.get(slot)
+                .is_some_and(|item| item.name.eq_ignore_ascii_case(item_name))
+        })
+    }
+
+    pub fn attack_bonus(&self) -> i32 {
+        power(&self.weapon) + power(&self.trinket)
+    }
+
+    pub fn defense_bonus(&self) -> i32 {
+        power(&self.armor)
+    }
+}
+
+fn power(item: &Option<Item>) -> i32 {
+    item.as_ref().and_then(|i| i.power).unwrap_or(0)
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Everything that can go wrong while loading or running a game
+#[derive(Debug)]
+pub enum GameError {
+    /// A file could not be read or written
+    Io { path: PathBuf, source: io::Error },
+    /// A file was read but its JSON didn't match what the game expects
+    Parse { path: PathBuf, source: serde_json::Error },
+    MissingRoom(String),
+    MissingEnemy(String),
+    MissingLevel(String),
+    MissingQuest(String),
+    MissingRecipe(String),
+    /// A save file the migration pipeline can't make sense of
+    InvalidSave(String),
+    /// A save written by a newer version of the game
+    UnsupportedSaveVersion { found: u64, supported: u32 },
+}
+
+pub type GameResult<T> = Result<T, GameError>;
+
+impl GameError {
+    pub fn io(path: impl AsRef<Path>, source: io::Error) -> Self {
+        GameError::Io { path: path.as_ref().to_path_buf(), source }
+    }
+
+    pub fn parse(path: impl AsRef<Path>, source: serde_json::Error) -> Self {
+        GameError::Parse { path: path.as_ref().to_path_buf(), source }
+    }
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            GameError::Parse { path, source } => write!(f, "{} is malformed: {}", path.display(), source),
+            GameError::MissingRoom(id) => write!(f, "Room '{}' does not exist", id),
+            GameError::MissingEnemy(name) => write!(f, "Enemy '{}' not found", name),
+            GameError::MissingLevel(id) => write!(f, "Level '{}' not found in campaign", id),
+            GameError::MissingQuest(id) => write!(f, "Quest '{}' not found", id),
+            GameError::MissingRecipe(id) => write!(f, "Recipe '{}' not found", id),
+            GameError::InvalidSave(reason) => write!(f, "Invalid save file: {}", reason),
+            GameError::UnsupportedSaveVersion { found, supported } => write!(
+                f,
+                "Save format version {} is newer than this game supports (version {}). Please update the game.",
+                found, supported
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GameError::Io { source, .. } => Some(source),
+            GameError::Parse { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+    use crate::save_load::read_json;
+
+    #[test]
+    fn missing_files_name_the_path() {
+        let err = read_json::<serde_json::Value>("no/such/file.json").unwrap_err();
+        assert!(matches!(err, GameError::Io { .. }));
+        assert!(err.to_string().starts_with("no/such/file.json: "));
+        assert!(err.source().is_some());
+    }
+
+    #[test]
+    fn malformed_files_name_the_path() {
+        let path = std::env::temp_dir().join(format!("shards_of_aether_malformed_{}.json", std::process::id()));
+        std::fs::write(&path, "{ not json").unwrap();
+        let err = read_json::<serde_json::Value>(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(err, GameError::Parse { .. }));
+        assert!(err.to_string().contains("is malformed"));
+    }
+
+    #[test]
+    fn lookup_errors_name_what_was_missing() {
+        assert_eq!(GameError::MissingRoom("vault".to_string()).to_string(), "Room 'vault' does not exist");
+        assert_eq!(GameError::MissingLevel("9".to_string()).to_string(), "Level '9' not found in campaign");
+        assert!(GameError::MissingEnemy("Orc".to_string()).source().is_none());
+    }
+}
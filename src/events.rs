@@ -0,0 +1,188 @@
+use crate::effects::EffectKind;
+use crate::items::Item;
+use crate::equipment::EquipSlot;
+use crate::player::Player;
+use crate::save_load::SaveMeta;
+
+/// What the player can see of a room
+#[derive(Clone, Debug)]
+pub struct RoomView {
+    pub id: String,
+    pub description: String,
+    /// Item names with their quantities
+    pub items: Vec<(String, u32)>,
+    pub exits: Vec<String>,
+    pub locked_exits: Vec<String>,
+    /// Visible containers and whether each is open
+    pub containers: Vec<(String, bool)>,
+    pub enemy: Option<String>,
+    /// Names of the NPCs here
+    pub npcs: Vec<String>,
+    /// Name of the shop here
+    pub shop: Option<String>,
+}
+
+/// A quest as shown in the journal
+#[derive(Clone, Debug)]
+pub struct QuestView {
+    pub title: String,
+    pub description: String,
+    pub completed: bool,
+    /// Description, progress and count needed for each objective
+    pub objectives: Vec<(String, u32, u32)>,
+}
+
+/// A known recipe as shown by `recipes`
+#[derive(Clone, Debug)]
+pub struct RecipeView {
+    pub name: String,
+    /// Name and count of each input
+    pub inputs: Vec<(String, u32)>,
+    pub output: (String, u32),
+    pub room: Option<String>,
+    pub tool: Option<String>,
+}
+
+/// Everything the engine reports back to a frontend.
+/// The engine never prints; frontends decide how to present these.
+#[derive(Clone, Debug)]
+pub enum GameEvent {
+    // === GENERAL ===
+    Welcome { name: String },
+    Help,
+    Status(Box<Player>),
+    Inventory(Vec<Item>),
+    Saved(String),
+    Loaded(String),
+    SaveList(Vec<(String, SaveMeta)>),
+    NoSuchSave(String),
+    LoadFailed { slot: String, reason: String },
+    SaveFailed { slot: String, reason: String },
+    InvalidSlotName(String),
+    Quit,
+    Unknown(String),
+    /// A `GameError` the session recovered from, already formatted for display
+    Error(String),
+    Narration(String),
+
+    // === LEVELS ===
+    LevelEntered { title: String },
+    LevelCompleted { text: Vec<String> },
+
+    // === EXPLORATION ===
+    Moved { direction: String, room: String },
+    NoExit,
+    ExitLocked { direction: String, message: Option<String> },
+    ExitUnlocked { item: String, direction: String },
+    ExitOpened { direction: String },
+    Teleported { room: String },
+    RoomDescribed(RoomView),
+    FeatureExamined { name: String, description: String },
+    NothingToExamine(String),
+    Map(Vec<String>),
+
+    // === ITEMS ===
+    ItemExamined(Item),
+    ItemTaken { item: String, count: u32 },
+    InventoryFull(String),
+    ItemDropped { item: String, count: u32 },
+    ItemReceived { item: String, count: u32 },
+    ItemHandedOver { item: String, count: u32 },
+    NoSuchContainer(String),
+    ItemStored { item: String, count: u32, container: String },
+    ContainerOpened { container: String, items: Vec<(String, u32)> },
+    ContainerLocked { container: String, message: Option<String> },
+    ContainerUnlocked { container: String, item: String },
+    ContainerClosed(String),
+    Searched { found: Vec<String> },
+    ItemNotHere(String),
+    NotInInventory(String),
+    ItemHealed { item: String, amount: i32, health: i32 },
+    ItemNoEffect(String),
+    Equipped { item: String, slot: EquipSlot, bonus: i32 },
+    Unequipped { item: String, slot: EquipSlot },
+    NotEquippable(String),
+    NotEquipped(String),
+    QuestItemPlaced { item: String, target: String },
+    CannotUseHere(String),
+    NotUsable,
+    ItemUsed(String),
+
+    // === DIALOGUE ===
+    NoSuchNpc(String),
+    DialogueLine { npc: String, text: String, choices: Vec<String> },
+    DialogueEnded { npc: String },
+    InvalidChoice,
+    NotTalking,
+
+    // === SHOPS ===
+    NoShop,
+    /// Shop name, the player's gold, and each ware's name, price and units left (None = unlimited)
+    ShopList { shop: String, gold: u32, wares: Vec<(String, u32, Option<u32>)> },
+    NotForSale(String),
+    NotEnoughGold { item: String, price: u32 },
+    Bought { item: String, count: u32, cost: u32 },
+    Sold { item: String, count: u32, gold: u32 },
+    CannotSell(String),
+
+    // === CRAFTING ===
+    Recipes(Vec<RecipeView>),
+    RecipeLearned(String),
+    RecipeAlreadyKnown(String),
+    UnknownRecipe(String),
+    CraftNeedsRoom { recipe: String, room: String },
+    CraftNeedsTool { recipe: String, tool: String },
+    /// Each missing input with how many more are needed
+    MissingIngredients { recipe: String, missing: Vec<(String, u32)> },
+    Crafted { item: String, count: u32 },
+
+    // === STATUS EFFECTS ===
+    // `target` is None for the player, or the enemy's name
+    EffectApplied { target: Option<String>, effect: EffectKind, turns: u32 },
+    /// HP lost (negative) or regained this turn
+    EffectTick { target: Option<String>, effect: EffectKind, change: i32, health: i32 },
+    EffectEnded { target: Option<String>, effect: EffectKind },
+    Stunned { target: Option<String> },
+
+    // === PLAYER ===
+    QuestStarted { title: String },
+    ObjectiveProgress { objective: String, progress: u32, needed: u32 },
+    QuestCompleted { title: String },
+    Journal(Vec<QuestView>),
+    XpGained(i32),
+    LevelUp { level: i32, max_health: i32 },
+    Recovered { amount: i32, health: i32, max_health: i32 },
+
+    // === COMBAT ===
+    EnemyAppears { name: String, description: String },
+    EnemyExamined { name: String, description: String, health: i32, attack: i32 },
+    CombatStatus {
+        health: i32,
+        effects: Vec<(EffectKind, u32)>,
+        enemy: String,
+        enemy_health: i32,
+        enemy_effects: Vec<(EffectKind, u32)>,
+    },
+    DamageDealt { enemy: String, damage: i32, critical: bool },
+    DamageTaken { enemy: String, damage: i32, blocked: i32, critical: bool },
+    AttackMissed { enemy: String },
+    EnemyMissed { enemy: String },
+    EnemyAbility { enemy: String, ability: String, message: Option<String> },
+    EnemyHealed { enemy: String, amount: i32, health: i32 },
+    EnemyBraced { enemy: String },
+    /// The enemy's guard softened the player's hit
+    EnemyGuarded { enemy: String },
+    EnemySummoned { enemy: String, summoned: String },
+    EnemyFled { enemy: String },
+    Braced,
+    NoHealingItems,
+    InvalidCombatAction,
+    NotInCombat,
+    EnemyDefeated(String),
+    LootDropped { enemy: String, item: String },
+    GoldLooted { enemy: String, amount: u32 },
+    Fled { room: String },
+    PlayerDefeated,
+    /// Sent instead of acting while the player lies defeated
+    StillDefeated,
+}
@@ -1,17 +1,270 @@
-use serde::{Serialize, Deserialize};
+use serde::{de, Serialize, Deserialize, Deserializer};
+use serde_json::{Map, Value};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::path::Path;
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+use crate::effects::{Affliction, EffectKind, StatusEffect};
+use crate::error::GameResult;
+use crate::save_load::read_json;
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum ItemType {
     Healing,
     Weapon,
+    Armor,
+    Trinket,
     Quest,
     Utility,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Item {
+    /// Registry id this item was built from, if any
+    #[serde(default)]
+    pub id: Option<String>,
     pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Extra lore shown only when the item is examined
+    #[serde(default)]
+    pub flavor: Option<String>,
     pub item_type: ItemType,
     pub power: Option<i32>,
     pub usable_on: Option<String>,
+    /// Stackable items share one inventory entry per name
+    #[serde(default)]
+    pub stackable: bool,
+    #[serde(default = "default_quantity")]
+    pub quantity: u32,
+    /// Weight of a single unit, counted against the campaign's carry limit
+    #[serde(default)]
+    pub weight: u32,
+    /// Price in gold at shops; merchants buy it back for half
+    #[serde(default)]
+    pub value: u32,
+    /// Recipe id learned by using the item, like a scroll
+    #[serde(default)]
+    pub teaches: Option<String>,
+    /// Effects put on whoever uses the item
+    #[serde(default)]
+    pub effects: Vec<StatusEffect>,
+    /// Effects a weapon may put on the enemy with each hit
+    #[serde(default)]
+    pub inflicts: Vec<Affliction>,
+    /// Effects the item removes when used
+    #[serde(default)]
+    pub cures: Vec<EffectKind>,
+}
+
+fn default_quantity() -> u32 {
+    1
+}
+
+impl Item {
+    pub fn stacks_with(&self, other: &Item) -> bool {
+        self.stackable && other.stackable && self.name.eq_ignore_ascii_case(&other.name)
+    }
+
+    /// Splits off up to `count` units, leaving the rest behind
+    pub fn split(&mut self, count: u32) -> Item {
+        let count = count.min(self.quantity);
+        self.quantity -= count;
+        Item { quantity: count, ..self.clone() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ItemData {
+    pub items: HashMap<String, Item>,
+}
+
+// Global storage for all item definitions, keyed by id
+pub static ITEM_LIST: Lazy<RwLock<HashMap<String, Item>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Loads the item registry from JSON into global memory.
+/// Must run before anything that refers to items by id is loaded.
+pub fn load_items(path: impl AsRef<Path>) -> GameResult<HashMap<String, Item>> {
+    let mut item_data: ItemData = read_json(path)?;
+    for (id, item) in item_data.items.iter_mut() {
+        item.id = Some(id.clone());
+    }
+
+    let mut global_items = ITEM_LIST.write().unwrap();
+    *global_items = item_data.items.clone();
+
+    Ok(item_data.items)
+}
+
+/// Get a cloned item definition by id
+pub fn get_item(id: &str) -> Option<Item> {
+    let items = ITEM_LIST.read().unwrap();
+    items.get(id).cloned()
+}
+
+/// Get a cloned item definition by its display name
+pub fn get_item_by_name(name: &str) -> Option<Item> {
+    let items = ITEM_LIST.read().unwrap();
+    items.values().find(|i| i.name.eq_ignore_ascii_case(name)).cloned()
+}
+
+thread_local! {
+    // Only asset files refer to items by id; saves hold every item in full
+    // and must load the same whatever the registry currently says
+    static READING_ASSETS: Cell<bool> = const { Cell::new(false) };
+}
+
+// Puts the flag back as it was when dropped, even if reading panicked
+struct ReadingAssetsGuard {
+    outer: bool,
+}
+
+impl Drop for ReadingAssetsGuard {
+    fn drop(&mut self) {
+        READING_ASSETS.with(|flag| flag.set(self.outer));
+    }
+}
+
+/// Runs `read` with item ids resolved against the registry, for asset files
+pub(crate) fn resolving_ids<T>(read: impl FnOnce() -> T) -> T {
+    let _guard = ReadingAssetsGuard { outer: READING_ASSETS.with(|flag| flag.replace(true)) };
+    read()
+}
+
+fn reading_assets() -> bool {
+    READING_ASSETS.with(Cell::get)
+}
+
+/// An item as written in asset files: a registry id, or an object that
+/// either names an `id` and overrides some of its fields or stands alone
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ItemSpec {
+    Id(String),
+    Inline(Map<String, Value>),
+}
+
+impl ItemSpec {
+    fn resolve(self) -> Result<Item, String> {
+        let fields = match self {
+            ItemSpec::Id(id) => return get_item(&id).ok_or_else(|| format!("unknown item id '{}'", id)),
+            ItemSpec::Inline(fields) => fields,
+        };
+
+        let mut merged = match fields.get("id").and_then(Value::as_str) {
+            Some(id) => {
+                let base = get_item(id).ok_or_else(|| format!("unknown item id '{}'", id))?;
+                match serde_json::to_value(base).map_err(|e| e.to_string())? {
+                    Value::Object(base) => base,
+                    _ => Map::new(),
+                }
+            }
+            None => Map::new(),
+        };
+        merged.extend(fields);
+
+        serde_json::from_value(Value::Object(merged)).map_err(|e| e.to_string())
+    }
+}
+
+/// For item lists: `ItemSpec`s in asset files, full items anywhere else
+pub fn deserialize_items<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Item>, D::Error> {
+    if !reading_assets() {
+        return Vec::<Item>::deserialize(deserializer);
+    }
+    Vec::<ItemSpec>::deserialize(deserializer)?
+        .into_iter()
+        .map(|spec| spec.resolve().map_err(de::Error::custom))
+        .collect()
+}
+
+/// For a single item: an `ItemSpec` in asset files, a full item anywhere else
+pub fn deserialize_item<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Item, D::Error> {
+    if !reading_assets() {
+        return Item::deserialize(deserializer);
+    }
+    ItemSpec::deserialize(deserializer)?
+        .resolve()
+        .map_err(de::Error::custom)
+}
+
+/// For optional items: an `ItemSpec` in asset files, a full item anywhere else
+pub fn deserialize_optional_item<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Item>, D::Error> {
+    if !reading_assets() {
+        return Option::<Item>::deserialize(deserializer);
+    }
+    Option::<ItemSpec>::deserialize(deserializer)?
+        .map(|spec| spec.resolve().map_err(de::Error::custom))
+        .transpose()
+}
+
+/// Adds an item to a list, merging it into an existing stack where possible
+pub fn add_to_stack(items: &mut Vec<Item>, item: Item) {
+    match items.iter_mut().find(|i| i.stacks_with(&item)) {
+        Some(stack) => stack.quantity += item.quantity,
+        None => items.push(item),
+    }
+}
+
+/// Removes up to `count` units from the entry at `pos`, dropping the entry once empty
+pub fn take_from_stack(items: &mut Vec<Item>, pos: usize, count: u32) -> Item {
+    let taken = items[pos].split(count);
+    if items[pos].quantity == 0 {
+        items.remove(pos);
+    }
+    taken
+}
+
+/// Position of the first entry with this name
+pub fn find_item(items: &[Item], name: &str) -> Option<usize> {
+    items.iter().position(|i| i.name.eq_ignore_ascii_case(name))
+}
+
+/// Total units of an item across every entry with its name
+pub fn count_of(items: &[Item], name: &str) -> u32 {
+    items
+        .iter()
+        .filter(|i| i.name.eq_ignore_ascii_case(name))
+        .map(|i| i.quantity)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Deserialize)]
+    struct Shelf {
+        #[serde(deserialize_with = "deserialize_items")]
+        items: Vec<Item>,
+    }
+
+    #[test]
+    fn saved_items_load_as_written() {
+        let saved = json!({
+            "items": [{ "id": "no_such_item", "name": "Old Map", "item_type": "Utility", "power": null, "usable_on": null }]
+        });
+
+        let shelf: Shelf = serde_json::from_value(saved).unwrap();
+        assert_eq!(shelf.items[0].name, "Old Map");
+    }
+
+    #[test]
+    fn asset_ids_must_be_registered() {
+        let asset = json!({ "items": ["no_such_item"] });
+
+        let result = resolving_ids(|| serde_json::from_value::<Shelf>(asset));
+        assert!(result.is_err_and(|e| e.to_string().contains("unknown item id 'no_such_item'")));
+        assert!(!reading_assets());
+    }
+
+    #[test]
+    fn a_panicking_read_still_restores_the_flag() {
+        let result = std::panic::catch_unwind(|| resolving_ids(|| panic!("bad asset")));
+
+        assert!(result.is_err());
+        assert!(!reading_assets());
+    }
 }
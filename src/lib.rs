@@ -0,0 +1,24 @@
+//! Headless engine for Shards of Aether.
+//!
+//! Frontends drive the game through [`engine::Engine::apply`] and present the
+//! returned [`events::GameEvent`]s however they like.
+
+pub mod campaign;
+pub mod combat;
+pub mod commands;
+pub mod crafting;
+pub mod effects;
+pub mod enemies;
+pub mod engine;
+pub mod equipment;
+pub mod error;
+pub mod events;
+pub mod items;
+pub mod npcs;
+pub mod player;
+pub mod quests;
+pub mod save_load;
+pub mod shops;
+pub mod triggers;
+pub mod validate;
+pub mod world;
@@ -1,188 +1,131 @@
-mod commands;
-mod player;
-mod utils;
-mod world;
-mod combat;
-mod enemies;
 mod colors;
-mod save_load;
-mod items;
+mod render;
+mod utils;
 
-use std::error::Error;
 use std::io::{stdout, Write};
+use std::process;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use shards_of_aether::campaign::load_campaign;
+use shards_of_aether::commands::parse_command;
+use shards_of_aether::enemies::load_enemies;
+use shards_of_aether::engine::{Engine, EngineConfig};
+use shards_of_aether::error::GameError;
+use shards_of_aether::events::GameEvent;
+use shards_of_aether::crafting::load_recipes;
+use shards_of_aether::items::load_items;
+use shards_of_aether::npcs::load_npcs;
+use shards_of_aether::quests::load_quests;
+use shards_of_aether::save_load::default_save_dir;
+use shards_of_aether::validate::{self, validate_campaign};
 
-use commands::{parse_command, Command, print_help};
-use player::Player;
-use utils::get_input;
-use enemies::load_enemies;
 use colors::{MessageType, colored_text};
-use save_load::{save_game, load_game, load_world};
+use utils::get_input;
 
-fn main() {
-    let _enemies = load_enemies("assets/enemies.json");
-    let mut player = Player::new();
-    let mut world = load_level(&player).expect("Failed to load initial world");
-
-    // Print banner
-    print_current_level_banner(&player);
-
-    println!(
-        "{}",
-        colored_text(
-            &format!("Welcome, {} the Adventurer!", player.name),
-            MessageType::Info
-        )
-    );
-    world::look(&player, &world);
+/// A text-based adventure RPG
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Directory holding campaign.json, items.json, recipes.json, quests.json, npcs.json, enemies.json and the level files
+    #[arg(long, value_name = "DIR", default_value = "assets")]
+    assets: PathBuf,
 
-    // === MAIN GAME LOOP ===
-    loop {
-        print!("{}", colored_text("\n> ", MessageType::Action));
-        stdout().flush().unwrap();
-
-        let input = get_input().to_lowercase();
-        let command = parse_command(&input);
-
-        match command {
-            Command::Help => print_help(),
-            Command::Status => print_status(&player),
-            Command::Go(dir) => world::move_player(dir, &mut player, &mut world),
-            Command::Look => world::look(&player, &world),
-
-            Command::Take(item) => {
-                world::take_item(&item, &mut player, &mut world);
-                handle_level_progression(&mut player, &mut world);
-                // println!("DEBUG: flags = {:?}", player.flags);
-            }
-
-            Command::Use(item) => {
-                let completed = world::use_item(&item, &mut player, &mut world);
-
-                // println!("DEBUG: current_level = {}", player.current_level);
-                // println!("DEBUG: flags = {:?}", player.flags);
-
-                if completed {
-                    handle_level_progression(&mut player, &mut world);
-                    // println!("DEBUG: flags = {:?}", player.flags);
-                }
-            }
-            Command::Inventory => {
-                let inventory_display = player
-                    .inventory
-                    .iter()
-                    .map(|i| i.name.as_str())
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                println!(
-                    "{}",
-                    colored_text(
-                        &format!("Inventory: [{}]", inventory_display),
-                        MessageType::Item
-                    )
-                );
-            }
-
-            Command::Save => {
-                save_game(&player, &world, "save.json").unwrap();
-                println!("{}", colored_text("Game saved!", MessageType::Info));
-            }
-
-            Command::Load => {
-                load_game(&mut player, &mut world, "save.json").unwrap();
-                println!("{}", colored_text("Game loaded!", MessageType::Info));
-            }
-
-            Command::Quit => {
-                println!("{}", colored_text("Farewell, brave adventurer!", MessageType::Info));
-                break;
-            }
-
-            Command::Unknown(cmd) => {
-                println!("{}", colored_text(&format!("Unknown command: {}", cmd), MessageType::Warning));
-            }
-        }
-    }
-}
+    /// Directory holding save slots [default: per-user data directory]
+    #[arg(long, value_name = "PATH")]
+    save: Option<PathBuf>,
 
-// === LEVEL LOADING ===
+    /// Level to start at: a campaign level id or its position (0 = first)
+    #[arg(long, value_name = "N")]
+    level: Option<String>,
 
-// Load the appropriate world file for the player's level
-fn load_level(player: &Player) -> Result<world::World, Box<dyn Error>> {
-    match player.current_level {
-        0 => Ok(load_world("assets/tutorial.json")?),
-        n => Ok(load_world(&format!("assets/level{}.json", n))?),
-    }
+    /// Seed for combat and loot rolls; reuse it to replay a run exactly
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Disable colored output
+    #[arg(long)]
+    no_color: bool,
+
+    /// Name of your adventurer
+    #[arg(long, value_name = "NAME")]
+    player_name: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<CliCommand>,
 }
 
-// Display a banner based on current level number
-fn print_current_level_banner(player: &Player) {
-    match player.current_level {
-        0 => world::print_transition_banner("Tutorial: The Guild Hall"),
-        1 => world::print_transition_banner("Level 1: The Emerald Forest"),
-        2 => world::print_transition_banner("Level 2: The Desert Sands"),
-        3 => world::print_transition_banner("Sanctum of Aether: You Win!"),
-        _ => world::print_transition_banner("The Realm of Aether"),
+#[derive(Subcommand)]
+enum CliCommand {
+    /// Check every level and enemies.json for broken references; exits non-zero on errors
+    Validate {
+        /// Treat warnings (one-way exits, unreachable rooms, ...) as errors too
+        #[arg(long)]
+        strict: bool,
+    },
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if cli.no_color {
+        colored::control::set_override(false);
     }
-}   
+    let seed = cli.seed.unwrap_or_else(rand::random);
 
-// === LEVEL PROGRESSION ===
+    // Items first: enemies and levels refer to them by id
+    let items_path = cli.assets.join("items.json");
+    let items = load_items(&items_path).unwrap_or_else(|e| exit_with_error(e));
+    println!("Loaded {} items from {}", items.len(), items_path.display());
 
-fn handle_level_progression(player: &mut Player, world: &mut world::World) {
-    // Tutorial → Level 1
-    if player.flags.contains(&"tutorial_completed".to_string()) 
-        && !player.flags.contains(&"level1_loaded".to_string()) 
-    {
-        world::print_transition_banner("Level 1: The Emerald Forest");
-        player.flags.push("level1_loaded".to_string());
+    let enemies_path = cli.assets.join("enemies.json");
+    let enemies = load_enemies(&enemies_path).unwrap_or_else(|e| exit_with_error(e));
+    println!("Loaded {} enemies from {}", enemies.len(), enemies_path.display());
 
-        *world = load_world("assets/level1.json").expect("Failed to load Level 1");
-        player.current_room = "forest_entrance".to_string();
+    let quests_path = cli.assets.join("quests.json");
+    let quests = load_quests(&quests_path).unwrap_or_else(|e| exit_with_error(e));
 
-        player.current_level = 1;
+    let recipes = load_recipes(cli.assets.join("recipes.json")).unwrap_or_else(|e| exit_with_error(e));
+    let npcs = load_npcs(cli.assets.join("npcs.json")).unwrap_or_else(|e| exit_with_error(e));
 
-        world::look(player, world);
-    }
+    let campaign = load_campaign(cli.assets.join("campaign.json")).unwrap_or_else(|e| exit_with_error(e));
 
-    // Level 1 → Level 2
-    if player.flags.contains(&"level1_completed".to_string())
-        && !player.flags.contains(&"level2_loaded".to_string())
-    {
-        world::print_transition_banner("Level 2: The Desert Sands");
-        player.flags.push("level2_loaded".to_string());
-
-        *world = load_world("assets/level2.json").expect("Failed to load Level 2");
-        player.current_room = "desert_edge".to_string();
-        player.current_level = 2;
-        world::look(player, world);
+    if let Some(CliCommand::Validate { strict }) = cli.command {
+        let issues = validate_campaign(&campaign, &items, &enemies, &quests, &npcs, &recipes);
+        render::print_validation_report(&issues);
+
+        process::exit(if validate::fails(&issues, strict) { 1 } else { 0 });
     }
 
-    // Level 2 → Sanctum
-    if player.flags.contains(&"level2_completed".to_string())
-        && !player.flags.contains(&"sanctum_loaded".to_string())
-    {
-        world::print_transition_banner("Sanctum of Aether: You Win!");
-        player.flags.push("sanctum_loaded".to_string());
-
-        let saved_inventory = player.inventory.clone();
-        *world = load_world("assets/sanctum.json").expect("Failed to load Sanctum");
-        player.inventory = saved_inventory;
-        player.current_room = "sanctum".to_string();
-        world::look(player, world);
+    let config = EngineConfig {
+        save_dir: cli.save.unwrap_or_else(default_save_dir),
+        seed,
+        start_level: cli.level,
+        player_name: cli.player_name,
+    };
+    let mut engine = Engine::new(campaign, config).unwrap_or_else(|e| exit_with_error(e));
+    println!("🎲 Seed: {}", engine.seed());
+
+    render::render(&engine.start());
+
+    // === MAIN GAME LOOP ===
+    loop {
+        print!("{}", colored_text("\n> ", MessageType::Action));
+        let _ = stdout().flush();
+
+        // Closing stdin (Ctrl+D) quits like typing `quit`
+        let input = get_input().map_or_else(|| "quit".to_string(), |line| line.to_lowercase());
+        let events = engine.apply(parse_command(&input));
+        render::render(&events);
+
+        if events.iter().any(|e| matches!(e, GameEvent::Quit)) {
+            break;
+        }
     }
 }
 
-pub fn print_status(player: &Player) {
-    println!("{}", colored_text("=== Player Status ===", MessageType::Info));
-    println!("Name: {}", player.name);
-    println!("Level: {} | XP: {}/{}", player.level, player.xp, player.xp_to_next_level());
-    println!("Health: {} ❤️", player.health);
-    println!("Attack: {} ⚔️", player.attack_damage());
-    println!("Current Room: {}", player.current_room);
-
-    let inventory = if player.inventory.is_empty() {
-        "Empty".to_string()
-    } else {
-        player.inventory.iter().map(|i| i.name.clone()).collect::<Vec<_>>().join(", ")
-    };
-    println!("Inventory: [{}]", inventory);
+// Startup can't continue without its assets
+fn exit_with_error(error: GameError) -> ! {
+    eprintln!("{}", colored_text(&format!("Error: {}", error), MessageType::Error));
+    process::exit(1);
 }
@@ -0,0 +1,196 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::path::Path;
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+use crate::error::GameResult;
+use crate::events::GameEvent;
+use crate::player::Player;
+use crate::save_load::read_asset;
+use crate::triggers::{self, Action, Conditions};
+use crate::world::World;
+
+/// A character the player can talk to
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Npc {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Node every conversation opens with
+    #[serde(default = "default_start")]
+    pub start: String,
+    pub nodes: HashMap<String, DialogueNode>,
+}
+
+fn default_start() -> String {
+    "start".to_string()
+}
+
+/// One thing the NPC says, and what the player can answer
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DialogueNode {
+    pub text: String,
+    /// Run every time the node is reached
+    #[serde(default)]
+    pub actions: Vec<Action>,
+    #[serde(default)]
+    pub choices: Vec<Choice>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Choice {
+    pub text: String,
+    /// Node to go to; the conversation ends when left out
+    #[serde(default)]
+    pub next: Option<String>,
+    /// Hides the choice unless met
+    #[serde(default)]
+    pub conditions: Conditions,
+    #[serde(default)]
+    pub actions: Vec<Action>,
+}
+
+/// Where a conversation in progress stands
+#[derive(Clone, Debug)]
+pub struct Conversation {
+    pub npc: String,
+    pub node: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NpcData {
+    pub npcs: HashMap<String, Npc>,
+}
+
+// Global storage for all NPCs, keyed by id
+pub static NPC_LIST: Lazy<RwLock<HashMap<String, Npc>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Loads NPCs from JSON into global memory. Items must already be loaded.
+pub fn load_npcs(path: impl AsRef<Path>) -> GameResult<HashMap<String, Npc>> {
+    let npc_data: NpcData = read_asset(path)?;
+
+    let mut global_npcs = NPC_LIST.write().unwrap();
+    *global_npcs = npc_data.npcs.clone();
+
+    Ok(npc_data.npcs)
+}
+
+/// Get a cloned NPC by id
+pub fn get_npc(id: &str) -> Option<Npc> {
+    let npcs = NPC_LIST.read().unwrap();
+    npcs.get(id).cloned()
+}
+
+/// Finds an NPC in the player's room by id (with spaces for underscores too),
+/// or by any run of whole words from its name, like "guild master" or "aldric"
+pub fn find_here(target: &str, player: &Player, world: &World) -> Option<(String, Npc)> {
+    let room = world.rooms.get(&player.current_room)?;
+    room.npcs.iter().find_map(|id| {
+        let npc = get_npc(id)?;
+        let matches = id.eq_ignore_ascii_case(target)
+            || id.replace('_', " ").eq_ignore_ascii_case(target)
+            || names_match(&npc.name, target);
+        matches.then(|| (id.clone(), npc))
+    })
+}
+
+// Whether the words of `target` appear, in order and next to each other, in `name`
+fn names_match(name: &str, target: &str) -> bool {
+    let name: Vec<&str> = name.split_whitespace().collect();
+    let target: Vec<&str> = target.split_whitespace().collect();
+    !target.is_empty()
+        && name.windows(target.len()).any(|run| {
+            run.iter().zip(&target).all(|(word, wanted)| word.eq_ignore_ascii_case(wanted))
+        })
+}
+
+/// Starts a conversation with an NPC in the current room
+pub fn talk(target: &str, player: &mut Player, world: &mut World, events: &mut Vec<GameEvent>) -> Option<Conversation> {
+    let Some((id, npc)) = find_here(target, player, world) else {
+        events.push(GameEvent::NoSuchNpc(target.to_string()));
+        return None;
+    };
+    let start = npc.start.clone();
+    enter_node(&id, &npc, &start, player, world, events)
+}
+
+/// Picks the `number`th (from 1) choice shown in the current node
+pub fn choose(
+    conversation: &Conversation,
+    number: usize,
+    player: &mut Player,
+    world: &mut World,
+    events: &mut Vec<GameEvent>,
+) -> Option<Conversation> {
+    let npc = get_npc(&conversation.npc)?;
+    let node = npc.nodes.get(&conversation.node)?;
+
+    let Some(choice) = visible_choices(node, player).nth(number.wrapping_sub(1)).cloned() else {
+        events.push(GameEvent::InvalidChoice);
+        return Some(conversation.clone());
+    };
+
+    for action in choice.actions {
+        triggers::run(action, player, world, events);
+    }
+
+    match choice.next {
+        Some(next) => enter_node(&conversation.npc, &npc, &next, player, world, events),
+        None => {
+            events.push(GameEvent::DialogueEnded { npc: npc.name });
+            None
+        }
+    }
+}
+
+fn visible_choices<'a>(node: &'a DialogueNode, player: &'a Player) -> impl Iterator<Item = &'a Choice> {
+    node.choices.iter().filter(|c| c.conditions.met(player))
+}
+
+// Says a node's text and offers its choices; ends the conversation if there are none
+fn enter_node(
+    id: &str,
+    npc: &Npc,
+    node_id: &str,
+    player: &mut Player,
+    world: &mut World,
+    events: &mut Vec<GameEvent>,
+) -> Option<Conversation> {
+    let Some(node) = npc.nodes.get(node_id) else {
+        events.push(GameEvent::DialogueEnded { npc: npc.name.clone() });
+        return None;
+    };
+
+    for action in node.actions.iter().cloned() {
+        triggers::run(action, player, world, events);
+    }
+
+    let choices: Vec<String> = visible_choices(node, player).map(|c| c.text.clone()).collect();
+    let ended = choices.is_empty();
+    events.push(GameEvent::DialogueLine {
+        npc: npc.name.clone(),
+        text: node.text.clone(),
+        choices,
+    });
+
+    if ended {
+        events.push(GameEvent::DialogueEnded { npc: npc.name.clone() });
+        return None;
+    }
+    Some(Conversation { npc: id.to_string(), node: node_id.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_runs_of_whole_words() {
+        for target in ["guild master aldric", "Guild Master", "master aldric", "aldric", "GUILD"] {
+            assert!(names_match("Guild Master Aldric", target), "{}", target);
+        }
+        for target in ["", "guild aldric", "master guild", "ald", "guild master aldric the wise"] {
+            assert!(!names_match("Guild Master Aldric", target), "{}", target);
+        }
+    }
+}
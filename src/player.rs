@@ -1,7 +1,11 @@
 use serde::{Serialize, Deserialize};
-use crate::items::Item;
+use crate::items::{self, Item};
+use crate::effects::{self, StatusEffect};
+use crate::events::GameEvent;
+use crate::equipment::{EquipSlot, Equipment};
+use crate::quests::QuestLog;
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Player {
     pub name: String,
     pub health: i32,
@@ -9,12 +13,36 @@ pub struct Player {
     pub xp: i32,
     pub level: i32,
     pub base_attack: i32,
+    #[serde(default)]
+    pub defense: i32,
     pub current_room: String,
     pub inventory: Vec<Item>,
+    #[serde(default)]
+    pub gold: u32,
+    #[serde(default)]
+    pub equipment: Equipment,
     pub flags: Vec<String>,
+    #[serde(default)]
+    pub quests: QuestLog,
+    /// Ids of recipes learned along the way
+    #[serde(default)]
+    pub recipes: Vec<String>,
+    #[serde(default)]
+    pub effects: Vec<StatusEffect>,
     pub current_level: usize,
 }
 
+/// Optional carry limits, set per campaign
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct InventoryLimits {
+    /// Most separate stacks the player can hold
+    #[serde(default)]
+    pub max_slots: Option<usize>,
+    /// Most total weight the player can hold
+    #[serde(default)]
+    pub max_weight: Option<u32>,
+}
+
 impl Player {
     pub fn new() -> Self {
         Self {
@@ -24,10 +52,16 @@ impl Player {
             xp: 0,
             level: 1,
             base_attack: 10,
+            defense: 0,
             current_room: "tutorial_hall".to_string(),
             inventory: Vec::new(),
+            gold: 0,
+            equipment: Equipment::default(),
             flags: Vec::new(),
-            current_level: 0, // 0 = tutorial, 1 = level1, 2 = level2, etc.
+            quests: QuestLog::default(),
+            recipes: Vec::new(),
+            effects: Vec::new(),
+            current_level: 0, // index into the campaign's level list
         }
     }
 
@@ -37,42 +71,256 @@ impl Player {
     }
 
     // Adds XP and automatically checks for level up
-    pub fn add_xp(&mut self, amount: i32) {
+    pub fn add_xp(&mut self, amount: i32, events: &mut Vec<GameEvent>) {
         self.xp += amount;
-        println!("✨ You gained {} XP!", amount);
+        events.push(GameEvent::XpGained(amount));
 
         // Automatically level up if XP exceeds threshold
         while self.xp >= self.xp_to_next_level() {
             self.xp -= self.xp_to_next_level();
-            self.level_up();
+            self.level_up(events);
         }
     }
 
     // Level-up stat increases
-    pub fn level_up(&mut self) {
+    pub fn level_up(&mut self, events: &mut Vec<GameEvent>) {
         self.level += 1;
 
         // Stat increases per level
         self.max_health += 20;
         self.base_attack += 3;
+        self.defense += 1;
 
         // Restore health on level-up
         self.health = self.max_health;
 
-        println!(
-            "🎉 You reached Level {}!\n❤️ Health restored to {}!\n⚔️ Attack power increased!",
-            self.level, self.max_health
-        );
+        events.push(GameEvent::LevelUp {
+            level: self.level,
+            max_health: self.max_health,
+        });
     }
 
-    // Damage calculation (scales with level)
+    // Damage calculation (scales with level and equipped gear)
     pub fn attack_damage(&self) -> i32 {
-        self.base_attack + (self.level * 2)
+        self.base_attack + (self.level * 2) + self.equipment.attack_bonus()
+    }
+
+    // Base defense plus equipped armor
+    pub fn total_defense(&self) -> i32 {
+        self.defense + self.equipment.defense_bonus()
+    }
+
+    // Damage actually taken from a hit; guarding halves what gets through armor
+    pub fn mitigate(&self, attack: i32, guarding: bool) -> i32 {
+        let through_armor = (attack - self.total_defense()).max(0);
+        if guarding {
+            through_armor / 2
+        } else {
+            through_armor.max(1)
+        }
+    }
+
+    // Puts an item in the inventory, stacking it where possible
+    pub fn add_item(&mut self, item: Item) {
+        items::add_to_stack(&mut self.inventory, item);
+    }
+
+    pub fn carried_weight(&self) -> u32 {
+        self.inventory.iter().map(|i| i.weight * i.quantity).sum()
+    }
+
+    // Whether one more unit of this item fits within the limits
+    pub fn can_carry(&self, item: &Item, limits: &InventoryLimits) -> bool {
+        let fits_weight = limits
+            .max_weight
+            .is_none_or(|max| self.carried_weight() + item.weight <= max);
+        let fits_slots = limits.max_slots.is_none_or(|max| {
+            self.inventory.len() < max || self.inventory.iter().any(|i| i.stacks_with(item))
+        });
+        fits_weight && fits_slots
+    }
+
+    // Moves an item from the inventory into its slot, swapping out whatever was there
+    pub fn equip(&mut self, item_name: &str, events: &mut Vec<GameEvent>) {
+        let Some(pos) = items::find_item(&self.inventory, item_name) else {
+            events.push(GameEvent::NotInInventory(item_name.to_string()));
+            return;
+        };
+
+        let Some(slot) = EquipSlot::for_item(&self.inventory[pos]) else {
+            events.push(GameEvent::NotEquippable(self.inventory[pos].name.clone()));
+            return;
+        };
+
+        let item = items::take_from_stack(&mut self.inventory, pos, 1);
+        events.push(GameEvent::Equipped {
+            item: item.name.clone(),
+            slot,
+            bonus: item.power.unwrap_or(0),
+        });
+
+        if let Some(previous) = self.equipment.slot_mut(slot).replace(item) {
+            events.push(GameEvent::Unequipped { item: previous.name.clone(), slot });
+            self.add_item(previous);
+        }
+    }
+
+    // Takes off an item, named either by slot or by item name
+    pub fn unequip(&mut self, target: &str, events: &mut Vec<GameEvent>) {
+        let Some(slot) = EquipSlot::parse(target).or_else(|| self.equipment.find(target)) else {
+            events.push(GameEvent::NotEquipped(target.to_string()));
+            return;
+        };
+
+        match self.equipment.slot_mut(slot).take() {
+            Some(item) => {
+                events.push(GameEvent::Unequipped { item: item.name.clone(), slot });
+                self.add_item(item);
+            }
+            None => events.push(GameEvent::NotEquipped(target.to_string())),
+        }
     }
 
     // Ensures HP doesn’t exceed max
-    pub fn heal(&mut self, amount: i32) {
+    pub fn heal(&mut self, amount: i32, events: &mut Vec<GameEvent>) {
         self.health = (self.health + amount).min(self.max_health);
-        println!("💖 You recovered {} HP! (Current HP: {}/{})", amount, self.health, self.max_health);
+        events.push(GameEvent::Recovered {
+            amount,
+            health: self.health,
+            max_health: self.max_health,
+        });
+    }
+
+    /// Applies a consumed item's cures and effects; false if it has neither
+    pub fn apply_item_effects(&mut self, item: &Item, events: &mut Vec<GameEvent>) -> bool {
+        effects::cure(&mut self.effects, &item.cures, &None, events);
+        for effect in &item.effects {
+            effects::apply(&mut self.effects, *effect, &None, events);
+        }
+        !item.cures.is_empty() || !item.effects.is_empty()
+    }
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn gear(name: &str, item_type: &str, power: i32) -> Item {
+        serde_json::from_value(json!({ "name": name, "item_type": item_type, "power": power, "usable_on": null }))
+            .unwrap()
+    }
+
+    #[test]
+    fn defense_softens_hits_but_never_below_one() {
+        let mut player = Player::new();
+        assert_eq!(player.mitigate(10, false), 10);
+
+        player.defense = 4;
+        assert_eq!(player.mitigate(10, false), 6);
+        assert_eq!(player.mitigate(3, false), 1);
+    }
+
+    #[test]
+    fn guarding_halves_what_gets_through_armor() {
+        let mut player = Player::new();
+        player.defense = 4;
+        assert_eq!(player.mitigate(10, true), 3);
+        // A guarded weak blow can be negated entirely
+        assert_eq!(player.mitigate(5, true), 0);
+    }
+
+    #[test]
+    fn levelling_up_raises_defense() {
+        let mut player = Player::new();
+        let mut events = Vec::new();
+        player.level_up(&mut events);
+        assert_eq!(player.defense, 1);
+        assert_eq!(player.health, player.max_health);
+    }
+
+    #[test]
+    fn equipped_gear_adds_to_attack_and_defense() {
+        let mut player = Player::new();
+        let base = player.attack_damage();
+        player.add_item(gear("Sword", "Weapon", 5));
+        player.add_item(gear("Mail", "Armor", 3));
+        player.add_item(gear("Ring", "Trinket", 2));
+        let mut events = Vec::new();
+
+        for name in ["sword", "mail", "ring"] {
+            player.equip(name, &mut events);
+        }
+
+        assert!(player.inventory.is_empty());
+        assert_eq!(player.attack_damage(), base + 7);
+        assert_eq!(player.total_defense(), 3);
+    }
+
+    #[test]
+    fn equipping_swaps_out_the_old_item() {
+        let mut player = Player::new();
+        player.add_item(gear("Sword", "Weapon", 5));
+        player.add_item(gear("Axe", "Weapon", 8));
+        let mut events = Vec::new();
+
+        player.equip("Sword", &mut events);
+        player.equip("Axe", &mut events);
+
+        assert_eq!(player.equipment.weapon.as_ref().map(|i| i.name.as_str()), Some("Axe"));
+        assert_eq!(player.inventory.len(), 1);
+        assert_eq!(player.inventory[0].name, "Sword");
+        // Swapping never stacks bonuses
+        assert_eq!(player.equipment.attack_bonus(), 8);
+    }
+
+    #[test]
+    fn unequips_by_slot_or_by_name() {
+        let mut player = Player::new();
+        player.add_item(gear("Sword", "Weapon", 5));
+        player.add_item(gear("Mail", "Armor", 3));
+        let mut events = Vec::new();
+        player.equip("Sword", &mut events);
+        player.equip("Mail", &mut events);
+
+        player.unequip("weapon", &mut events);
+        player.unequip("mail", &mut events);
+
+        assert!(player.equipment.weapon.is_none() && player.equipment.armor.is_none());
+        assert_eq!(player.inventory.len(), 2);
+        assert_eq!(player.total_defense(), 0);
+
+        events.clear();
+        player.unequip("trinket", &mut events);
+        assert!(matches!(&events[..], [GameEvent::NotEquipped(_)]));
+    }
+
+    #[test]
+    fn only_gear_can_be_equipped() {
+        let mut player = Player::new();
+        player.add_item(gear("Herb", "Healing", 10));
+        let mut events = Vec::new();
+
+        player.equip("Herb", &mut events);
+
+        assert_eq!(player.inventory.len(), 1);
+        assert!(matches!(&events[..], [GameEvent::NotEquippable(name)] if name == "Herb"));
+    }
+
+    #[test]
+    fn equipment_is_saved_with_the_player() {
+        let mut player = Player::new();
+        player.add_item(gear("Sword", "Weapon", 5));
+        player.equip("Sword", &mut Vec::new());
+
+        let restored: Player = serde_json::from_str(&serde_json::to_string(&player).unwrap()).unwrap();
+        assert_eq!(restored.attack_damage(), player.attack_damage());
+        assert_eq!(restored.equipment.find("sword"), Some(EquipSlot::Weapon));
     }
 }
@@ -0,0 +1,304 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::path::Path;
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+use crate::error::{GameError, GameResult};
+use crate::events::{GameEvent, QuestView};
+use crate::items::{self, Item};
+use crate::player::Player;
+use crate::save_load::read_asset;
+
+/// Something the player does that can advance an objective
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectiveKind {
+    Take,
+    Kill,
+    Use,
+    Visit,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Objective {
+    pub kind: ObjectiveKind,
+    /// Item, enemy or room id, depending on the kind
+    pub target: String,
+    #[serde(default = "default_count")]
+    pub count: u32,
+    pub description: String,
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+/// Handed out when every objective of a quest is done
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Reward {
+    #[serde(default)]
+    pub xp: i32,
+    #[serde(default, deserialize_with = "items::deserialize_items")]
+    pub items: Vec<Item>,
+    #[serde(default)]
+    pub flag: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Quest {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub objectives: Vec<Objective>,
+    #[serde(default)]
+    pub reward: Reward,
+}
+
+/// How far along the player is in one active quest
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct QuestProgress {
+    pub id: String,
+    /// Count so far for each objective, in order
+    pub progress: Vec<u32>,
+}
+
+/// The player's quests, saved along with them
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct QuestLog {
+    #[serde(default)]
+    pub active: Vec<QuestProgress>,
+    #[serde(default)]
+    pub completed: Vec<String>,
+}
+
+impl QuestLog {
+    pub fn knows(&self, id: &str) -> bool {
+        self.completed.iter().any(|q| q == id) || self.active.iter().any(|q| q.id == id)
+    }
+
+    pub fn is_completed(&self, id: &str) -> bool {
+        self.completed.iter().any(|q| q == id)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QuestData {
+    pub quests: HashMap<String, Quest>,
+}
+
+// Global storage for all quest definitions, keyed by id
+pub static QUEST_LIST: Lazy<RwLock<HashMap<String, Quest>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Loads quests from JSON into global memory. Items must already be loaded.
+pub fn load_quests(path: impl AsRef<Path>) -> GameResult<HashMap<String, Quest>> {
+    let quest_data: QuestData = read_asset(path)?;
+
+    let mut global_quests = QUEST_LIST.write().unwrap();
+    *global_quests = quest_data.quests.clone();
+
+    Ok(quest_data.quests)
+}
+
+/// Get a cloned quest by id
+pub fn get_quest(id: &str) -> Option<Quest> {
+    let quests = QUEST_LIST.read().unwrap();
+    quests.get(id).cloned()
+}
+
+/// Adds a quest to the player's log, unless it's already there
+pub fn start(id: &str, player: &mut Player, events: &mut Vec<GameEvent>) {
+    if player.quests.knows(id) {
+        return;
+    }
+    let Some(quest) = get_quest(id) else {
+        events.push(GameEvent::Error(GameError::MissingQuest(id.to_string()).to_string()));
+        return;
+    };
+
+    // Items picked up before the quest started still count towards it
+    let progress: Vec<u32> = quest
+        .objectives
+        .iter()
+        .map(|objective| match objective.kind {
+            ObjectiveKind::Take => items::count_of(&player.inventory, &objective.target).min(objective.count),
+            _ => 0,
+        })
+        .collect();
+
+    events.push(GameEvent::QuestStarted { title: quest.title.clone() });
+    for (objective, progress) in quest.objectives.iter().zip(&progress).filter(|(_, p)| **p > 0) {
+        events.push(GameEvent::ObjectiveProgress {
+            objective: objective.description.clone(),
+            progress: *progress,
+            needed: objective.count,
+        });
+    }
+    player.quests.active.push(QuestProgress { id: id.to_string(), progress });
+    complete_finished(player, events);
+}
+
+/// Advances every active objective matching what the player just did
+pub fn record(kind: ObjectiveKind, target: &str, player: &mut Player, events: &mut Vec<GameEvent>) {
+    for entry in player.quests.active.iter_mut() {
+        let Some(quest) = get_quest(&entry.id) else { continue };
+
+        for (objective, progress) in quest.objectives.iter().zip(entry.progress.iter_mut()) {
+            if objective.kind != kind || !objective.target.eq_ignore_ascii_case(target) || *progress >= objective.count {
+                continue;
+            }
+            // Take objectives go by what is carried, so dropping and picking up
+            // the same item again can't run them up
+            let reached = match kind {
+                ObjectiveKind::Take => items::count_of(&player.inventory, target).min(objective.count),
+                _ => *progress + 1,
+            };
+            if reached <= *progress {
+                continue;
+            }
+            *progress = reached;
+            events.push(GameEvent::ObjectiveProgress {
+                objective: objective.description.clone(),
+                progress: *progress,
+                needed: objective.count,
+            });
+        }
+    }
+    complete_finished(player, events);
+}
+
+// Moves every active quest with all objectives met to completed, handing out rewards
+fn complete_finished(player: &mut Player, events: &mut Vec<GameEvent>) {
+    let mut finished = Vec::new();
+
+    for entry in &player.quests.active {
+        let Some(quest) = get_quest(&entry.id) else { continue };
+        let done = quest
+            .objectives
+            .iter()
+            .zip(&entry.progress)
+            .all(|(objective, progress)| *progress >= objective.count);
+        if done {
+            finished.push((entry.id.clone(), quest));
+        }
+    }
+
+    for (id, quest) in finished {
+        player.quests.active.retain(|q| q.id != id);
+        player.quests.completed.push(id);
+        events.push(GameEvent::QuestCompleted { title: quest.title });
+        grant(quest.reward, player, events);
+    }
+}
+
+fn grant(reward: Reward, player: &mut Player, events: &mut Vec<GameEvent>) {
+    for item in reward.items {
+        events.push(GameEvent::ItemReceived { item: item.name.clone(), count: item.quantity });
+        player.add_item(item);
+    }
+    if let Some(flag) = reward.flag {
+        if !player.flags.contains(&flag) {
+            player.flags.push(flag);
+        }
+    }
+    if reward.xp > 0 {
+        player.add_xp(reward.xp, events);
+    }
+}
+
+/// Active quests first, then completed ones, for the journal
+pub fn journal(player: &Player) -> Vec<QuestView> {
+    let active = player.quests.active.iter().filter_map(|entry| {
+        let quest = get_quest(&entry.id)?;
+        Some(view(&quest, &entry.progress, false))
+    });
+    let completed = player.quests.completed.iter().filter_map(|id| {
+        let quest = get_quest(id)?;
+        let progress: Vec<u32> = quest.objectives.iter().map(|o| o.count).collect();
+        Some(view(&quest, &progress, true))
+    });
+    active.chain(completed).collect()
+}
+
+fn view(quest: &Quest, progress: &[u32], completed: bool) -> QuestView {
+    QuestView {
+        title: quest.title.clone(),
+        description: quest.description.clone(),
+        completed,
+        objectives: quest
+            .objectives
+            .iter()
+            .zip(progress)
+            .map(|(objective, progress)| (objective.description.clone(), *progress, objective.count))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // The registry is shared by every test, so each test uses quest ids and items of its own
+    fn register(id: &str, item: &str, count: u32) {
+        let quest: Quest = serde_json::from_value(json!({
+            "title": id,
+            "objectives": [
+                { "kind": "take", "target": item, "count": count, "description": "Gather" },
+                { "kind": "visit", "target": "hall", "description": "Go home" }
+            ]
+        }))
+        .unwrap();
+        QUEST_LIST.write().unwrap().insert(id.to_string(), quest);
+    }
+
+    fn stack(name: &str, quantity: u32) -> Item {
+        serde_json::from_value(json!({
+            "name": name,
+            "item_type": "Healing",
+            "power": 20,
+            "usable_on": null,
+            "stackable": true,
+            "quantity": quantity
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn items_already_carried_count_when_a_quest_starts() {
+        register("quest_test_gathering", "Quest Test Herb", 3);
+        let mut player = Player::new();
+        player.add_item(stack("Quest Test Herb", 2));
+
+        let mut events = Vec::new();
+        start("quest_test_gathering", &mut player, &mut events);
+        assert_eq!(player.quests.active[0].progress, vec![2, 0]);
+
+        player.add_item(stack("Quest Test Herb", 1));
+        record(ObjectiveKind::Take, "Quest Test Herb", &mut player, &mut events);
+        record(ObjectiveKind::Visit, "hall", &mut player, &mut events);
+        assert!(player.quests.is_completed("quest_test_gathering"));
+    }
+
+    #[test]
+    fn picking_the_same_item_up_again_does_not_advance_take_objectives() {
+        use crate::player::InventoryLimits;
+        use crate::world::{self, World};
+
+        register("quest_test_farming", "Quest Test Feather", 3);
+        let mut world: World = serde_json::from_value(json!({
+            "rooms": { "hall": { "id": "hall", "description": "", "items": [stack("Quest Test Feather", 1)] } }
+        }))
+        .unwrap();
+        let mut player = Player { current_room: "hall".to_string(), ..Player::new() };
+        let mut events = Vec::new();
+        start("quest_test_farming", &mut player, &mut events);
+
+        let limits = InventoryLimits::default();
+        for _ in 0..5 {
+            world::take_item("Quest Test Feather", None, &limits, &mut player, &mut world, &mut events);
+            world::drop_item("Quest Test Feather", None, &mut player, &mut world, &mut events);
+        }
+
+        assert_eq!(player.quests.active[0].progress, vec![1, 0]);
+    }
+}
@@ -0,0 +1,706 @@
+use shards_of_aether::effects::{self, EffectKind};
+use shards_of_aether::equipment::EquipSlot;
+use shards_of_aether::events::{GameEvent, QuestView, RecipeView, RoomView};
+use shards_of_aether::items::Item;
+use shards_of_aether::player::Player;
+use shards_of_aether::save_load::SaveMeta;
+use shards_of_aether::validate::{Issue, Severity};
+
+use chrono::{DateTime, Local};
+use itertools::Itertools;
+
+use crate::colors::{colored_text, MessageType};
+
+/// Prints a batch of engine events to the terminal
+pub fn render(events: &[GameEvent]) {
+    for event in events {
+        render_event(event);
+    }
+}
+
+fn render_event(event: &GameEvent) {
+    match event {
+        // === GENERAL ===
+        GameEvent::Welcome { name } => println!(
+            "{}",
+            colored_text(&format!("Welcome, {} the Adventurer!", name), MessageType::Info)
+        ),
+        GameEvent::Help => print_help(),
+        GameEvent::Status(player) => print_status(player),
+        GameEvent::Inventory(items) => {
+            let inventory_display = item_list(items);
+            println!(
+                "{}",
+                colored_text(&format!("Inventory: [{}]", inventory_display), MessageType::Item)
+            );
+        }
+        GameEvent::Saved(slot) => println!(
+            "{}",
+            colored_text(&format!("Game saved to slot '{}'!", slot), MessageType::Info)
+        ),
+        GameEvent::Loaded(slot) => println!(
+            "{}",
+            colored_text(&format!("Game loaded from slot '{}'!", slot), MessageType::Info)
+        ),
+        GameEvent::SaveList(saves) => print_saves(saves),
+        GameEvent::NoSuchSave(slot) => println!(
+            "{}",
+            colored_text(&format!("There is no save in slot '{}'.", slot), MessageType::Warning)
+        ),
+        GameEvent::LoadFailed { slot, reason } => println!(
+            "{}",
+            colored_text(&format!("Could not load slot '{}': {}", slot, reason), MessageType::Error)
+        ),
+        GameEvent::SaveFailed { slot, reason } => println!(
+            "{}",
+            colored_text(&format!("Could not save to slot '{}': {}", slot, reason), MessageType::Error)
+        ),
+        GameEvent::InvalidSlotName(slot) => println!(
+            "{}",
+            colored_text(
+                &format!("'{}' is not a valid slot name. Use letters, numbers, '-' and '_'.", slot),
+                MessageType::Warning
+            )
+        ),
+        GameEvent::Quit => println!("{}", colored_text("Farewell, brave adventurer!", MessageType::Info)),
+        GameEvent::Unknown(cmd) => println!(
+            "{}",
+            colored_text(&format!("Unknown command: {}", cmd), MessageType::Warning)
+        ),
+        GameEvent::Error(message) => println!("{}", colored_text(&format!("⚠️ {}", message), MessageType::Error)),
+        GameEvent::Narration(text) => println!("{}", text),
+
+        // === LEVELS ===
+        GameEvent::LevelEntered { title } => print_transition_banner(title),
+        GameEvent::LevelCompleted { text } => {
+            for line in text {
+                println!("{}", colored_text(line, MessageType::Success));
+            }
+        }
+
+        // === EXPLORATION ===
+        GameEvent::Moved { direction, room } => {
+            println!("You move {}.", direction);
+            println!("🧍 You have entered: {}", room);
+        }
+        GameEvent::NoExit => println!("You can't go that way."),
+        GameEvent::ExitLocked { direction, message } => match message {
+            Some(message) => println!("🔒 {}", message),
+            None => println!("🔒 The way {} is locked.", direction),
+        },
+        GameEvent::ExitUnlocked { item, direction } => println!(
+            "🔓 You use the {} and unlock the way {}!",
+            item, direction
+        ),
+        GameEvent::ExitOpened { direction } => println!("🔓 The way {} is open!", direction),
+        GameEvent::Teleported { room } => println!("✨ You are whisked away to {}!", room),
+        GameEvent::RoomDescribed(room) => print_room(room),
+        GameEvent::FeatureExamined { name, description } => {
+            println!("🔍 {}", colored_text(name, MessageType::Action));
+            println!("{}", description);
+        }
+        GameEvent::NothingToExamine(target) => println!("You see no {} here.", target),
+        GameEvent::Map(rows) => {
+            println!("--- Map ---");
+            for row in rows {
+                println!("{}", row);
+            }
+            println!("-----------");
+        }
+
+        // === ITEMS ===
+        GameEvent::ItemExamined(item) => print_item(item),
+        GameEvent::ItemTaken { item, count } => {
+            println!("You picked up: {}", colored_text(&with_count(item, *count), MessageType::Item))
+        }
+        GameEvent::ItemReceived { item, count } => {
+            println!("You receive: {}", colored_text(&with_count(item, *count), MessageType::Item))
+        }
+        GameEvent::ItemHandedOver { item, count } => {
+            println!("You hand over: {}", colored_text(&with_count(item, *count), MessageType::Item))
+        }
+        GameEvent::ItemDropped { item, count } => {
+            println!("You drop: {}", colored_text(&with_count(item, *count), MessageType::Item))
+        }
+        GameEvent::NoSuchContainer(container) => println!("There is no {} here.", container),
+        GameEvent::ItemStored { item, count, container } => println!(
+            "You put {} in the {}.",
+            colored_text(&with_count(item, *count), MessageType::Item),
+            container
+        ),
+        GameEvent::ContainerOpened { container, items } => {
+            if items.is_empty() {
+                println!("You open the {}. It's empty.", container);
+            } else {
+                println!("You open the {}. Inside you find:", container);
+                for (item, count) in items {
+                    println!(" - {}", colored_text(&with_count(item, *count), MessageType::Item));
+                }
+            }
+        }
+        GameEvent::ContainerLocked { container, message } => match message {
+            Some(message) => println!("🔒 {}", message),
+            None => println!("🔒 The {} is locked.", container),
+        },
+        GameEvent::ContainerUnlocked { container, item } => {
+            println!("🔓 You use the {} to unlock the {}.", item, container)
+        }
+        GameEvent::ContainerClosed(container) => println!("The {} is closed. Try opening it first.", container),
+        GameEvent::Searched { found } => {
+            if found.is_empty() {
+                println!("🔍 You search around but find nothing new.");
+            } else {
+                println!("🔍 Your search turns up: {}", found.join(", "));
+            }
+        }
+        GameEvent::InventoryFull(item) => {
+            println!("You can't carry any more. The {} stays where it is.", item)
+        }
+        GameEvent::ItemNotHere(item) => println!("There is no {} here.", item),
+        GameEvent::NotInInventory(item) => {
+            println!("You don't have a '{}' in your inventory.", item)
+        }
+        GameEvent::ItemHealed { item, amount, health } => println!(
+            "💖 You use {} and restore {} HP! Current HP: {}",
+            item, amount, health
+        ),
+        GameEvent::ItemNoEffect(item) => println!("💖 You use {}, but it had no effect.", item),
+        GameEvent::Equipped { item, slot: EquipSlot::Armor, bonus } => {
+            println!("🛡️ Equipped {}! Defense +{}", item, bonus)
+        }
+        GameEvent::Equipped { item, bonus, .. } => println!("⚔️ Equipped {}! Attack +{}", item, bonus),
+        GameEvent::Unequipped { item, slot } => {
+            println!("You take off the {} ({} slot).", item, slot.name())
+        }
+        GameEvent::NotEquippable(item) => println!("You can't equip the {}.", item),
+        GameEvent::NotEquipped(target) => println!("You have no {} equipped.", target),
+        GameEvent::QuestItemPlaced { item, target } => println!(
+            "✨ You place the {} on the {}. The path forward opens!",
+            item, target
+        ),
+        GameEvent::CannotUseHere(item) => println!("You can’t use the {} here.", item),
+        GameEvent::NotUsable => println!("That item can’t be used directly."),
+        GameEvent::ItemUsed(item) => println!("You use the {}.", item),
+
+        // === PLAYER ===
+        GameEvent::XpGained(amount) => println!("✨ You gained {} XP!", amount),
+        GameEvent::LevelUp { level, max_health } => println!(
+            "🎉 You reached Level {}!\n❤️ Health restored to {}!\n⚔️ Attack power increased!\n🛡️ Defense increased!",
+            level, max_health
+        ),
+        GameEvent::Recovered { amount, health, max_health } => println!(
+            "💖 You recovered {} HP! (Current HP: {}/{})",
+            amount, health, max_health
+        ),
+
+        GameEvent::QuestStarted { title } => println!(
+            "{}",
+            colored_text(&format!("📜 New quest: {}", title), MessageType::Info)
+        ),
+        GameEvent::ObjectiveProgress { objective, progress, needed } => {
+            println!("📜 {} ({}/{})", objective, progress, needed)
+        }
+        GameEvent::QuestCompleted { title } => println!(
+            "{}",
+            colored_text(&format!("📜 Quest complete: {}", title), MessageType::Success)
+        ),
+        GameEvent::Journal(quests) => print_journal(quests),
+
+        // === DIALOGUE ===
+        GameEvent::NoSuchNpc(npc) => println!("There is no {} here to talk to.", npc),
+        GameEvent::DialogueLine { npc, text, choices } => {
+            println!("{}: \"{}\"", colored_text(npc, MessageType::Action), text);
+            for (i, choice) in choices.iter().enumerate() {
+                println!("  {}. {}", i + 1, choice);
+            }
+            if !choices.is_empty() {
+                println!("{}", colored_text("Type a number to answer, or 'bye' to leave.", MessageType::Info));
+            }
+        }
+        GameEvent::DialogueEnded { npc } => println!("You end your conversation with {}.", npc),
+        GameEvent::InvalidChoice => println!("That's not one of the answers."),
+        GameEvent::NotTalking => println!("You're not talking to anyone."),
+
+        // === CRAFTING ===
+        GameEvent::Recipes(recipes) => print_recipes(recipes),
+        GameEvent::RecipeLearned(recipe) => println!(
+            "{}",
+            colored_text(&format!("📖 You learned to make {}!", recipe), MessageType::Success)
+        ),
+        GameEvent::RecipeAlreadyKnown(recipe) => println!("You already know how to make {}.", recipe),
+        GameEvent::UnknownRecipe(recipe) => println!("You don't know a recipe for '{}'.", recipe),
+        GameEvent::CraftNeedsRoom { recipe, room } => println!("{} can only be made at {}.", recipe, room),
+        GameEvent::CraftNeedsTool { recipe, tool } => println!("You need a {} to make {}.", tool, recipe),
+        GameEvent::MissingIngredients { recipe, missing } => {
+            let missing = missing.iter().map(|(item, count)| with_count(item, *count)).join(", ");
+            println!("You can't make {} yet. Still missing: {}", recipe, missing);
+        }
+        GameEvent::Crafted { item, count } => println!(
+            "⚗️ You made {}!",
+            colored_text(&with_count(item, *count), MessageType::Item)
+        ),
+
+        // === SHOPS ===
+        GameEvent::NoShop => println!("There is nobody here to trade with."),
+        GameEvent::ShopList { shop, gold, wares } => print_shop(shop, *gold, wares),
+        GameEvent::NotForSale(item) => println!("{} isn't for sale here.", item),
+        GameEvent::NotEnoughGold { item, price } => {
+            println!("You can't afford {} ({} gold).", item, price)
+        }
+        GameEvent::Bought { item, count, cost } => println!(
+            "You buy {} for {} gold.",
+            colored_text(&with_count(item, *count), MessageType::Item),
+            cost
+        ),
+        GameEvent::Sold { item, count, gold } => println!(
+            "You sell {} for {} gold.",
+            colored_text(&with_count(item, *count), MessageType::Item),
+            gold
+        ),
+        GameEvent::CannotSell(item) => println!("No merchant will buy {}.", item),
+
+        // === COMBAT ===
+        GameEvent::EnemyAppears { name, description } => {
+            println!("\n⚔️ A wild {} appears!", name);
+            println!("⚔️ You encounter a {}!", name);
+            println!("{}", description);
+        }
+        GameEvent::EnemyExamined { name, description, health, attack } => {
+            println!("🔍 {}", colored_text(name, MessageType::Enemy));
+            println!("{}", description);
+            println!("HP: {} | Attack: {}", health, attack);
+        }
+        GameEvent::CombatStatus { health, effects, enemy, enemy_health, enemy_effects } => {
+            println!(
+                "\n❤️ Your HP: {}{} | 💀 {}’s HP: {}{}",
+                health,
+                effect_tags(effects),
+                enemy,
+                enemy_health,
+                effect_tags(enemy_effects)
+            );
+            println!("Choose an action (attack / heal / defend / run):");
+        }
+        GameEvent::DamageDealt { enemy, damage, critical } => {
+            if *critical {
+                print!("💥 Critical hit! ");
+            }
+            println!("You strike the {} for {} damage!", enemy, damage)
+        }
+        GameEvent::AttackMissed { enemy } => println!("Your attack misses the {}!", enemy),
+        GameEvent::EnemyMissed { enemy } => println!("The {} attacks, but misses you!", enemy),
+        GameEvent::EnemyAbility { enemy, ability, message } => {
+            println!("{}", colored_text(&format!("⚡ The {} uses {}!", enemy, ability), MessageType::Enemy));
+            if let Some(message) = message {
+                println!("{}", message);
+            }
+        }
+        GameEvent::EnemyHealed { enemy, amount, health } => {
+            println!("💚 The {} recovers {} HP ({} HP).", enemy, amount, health)
+        }
+        GameEvent::EnemyBraced { enemy } => println!("🛡️ The {} braces for your next attack.", enemy),
+        GameEvent::EnemyGuarded { enemy } => println!("🛡️ The {}'s guard softens your blow!", enemy),
+        GameEvent::EnemySummoned { enemy, summoned } => println!(
+            "{}",
+            colored_text(
+                &format!("👁️ The {} calls forth a {}! It joins the fight once the {} is gone.", enemy, summoned, enemy),
+                MessageType::Warning
+            )
+        ),
+        GameEvent::EnemyFled { enemy } => println!("💨 The {} flees the fight!", enemy),
+        GameEvent::DamageTaken { enemy, damage, blocked, critical } => {
+            if *critical {
+                print!("💥 Critical hit! ");
+            }
+            match (damage, blocked) {
+                (0, _) => println!("🛡️ You block the {}’s attack completely!", enemy),
+                (_, 0) => println!("The {} attacks you for {} damage!", enemy, damage),
+                _ => println!("The {} attacks you for {} damage! (🛡️ {} blocked)", enemy, damage, blocked),
+            }
+        }
+        GameEvent::Braced => println!("🛡️ You brace yourself!"),
+        GameEvent::NoHealingItems => println!("You have no healing items!"),
+        GameEvent::InvalidCombatAction => println!("Unknown action. Type attack / heal / defend / run."),
+        GameEvent::NotInCombat => println!("There is nothing to fight here."),
+        GameEvent::EnemyDefeated(enemy) => println!("🎉 You defeated the {}!", enemy),
+        GameEvent::LootDropped { enemy, item } => println!(
+            "The {} dropped {}!",
+            enemy,
+            colored_text(item, MessageType::Item)
+        ),
+        GameEvent::GoldLooted { enemy, amount } => {
+            println!("🪙 The {} carried {} gold.", enemy, amount)
+        }
+        GameEvent::Fled { room } => {
+            println!("🏃 You flee from the battle!");
+            println!("You have escaped back to {}.", room);
+        }
+        GameEvent::PlayerDefeated => print_death_screen(),
+
+        // === STATUS EFFECTS ===
+        GameEvent::EffectApplied { target, effect, turns } => println!(
+            "{}",
+            colored_text(
+                &format!("🌀 {} {} {} for {}!", subject(target), be_verb(target), effect.name(), turns_text(*turns)),
+                effect_color(*effect)
+            )
+        ),
+        GameEvent::EffectTick { target, effect, change, health } => match effect {
+            EffectKind::Regen => println!("🌿 {} regain {} HP ({} HP).", subject(target), change, health),
+            _ => println!(
+                "🌀 {} {} {} HP to {} ({} HP).",
+                subject(target),
+                lose_verb(target),
+                -change,
+                effect_cause(*effect),
+                health
+            ),
+        },
+        GameEvent::EffectEnded { target, effect } => {
+            println!("{} {} no longer {}.", subject(target), be_verb(target), effect.name())
+        }
+        GameEvent::Stunned { target } => match target {
+            None => println!("💫 You are stunned and can't act!"),
+            Some(enemy) => println!("💫 The {} is stunned and can't act!", enemy),
+        },
+        GameEvent::StillDefeated => println!(
+            "{}",
+            colored_text("You have fallen. Type 'load' to return to your last save, or 'quit'.", MessageType::Warning)
+        ),
+    }
+}
+
+fn print_room(room: &RoomView) {
+    println!("{}", colored_text(&room.id, MessageType::Action));
+    println!("\n{}", room.description);
+
+    if !room.items.is_empty() {
+        println!("You see:");
+        for (item, count) in &room.items {
+            println!(" - {}", colored_text(&with_count(item, *count), MessageType::Item));
+        }
+    }
+
+    if !room.containers.is_empty() {
+        let containers = room.containers.iter()
+            .map(|(name, open)| {
+                let name = colored_text(name, MessageType::Item).to_string();
+                if *open { format!("{} (open)", name) } else { name }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!("Containers: {}", containers);
+    }
+
+    if !room.npcs.is_empty() {
+        let npcs = room.npcs.iter()
+            .map(|npc| colored_text(npc, MessageType::Action).to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!("People here: {}", npcs);
+    }
+
+    if let Some(shop) = &room.shop {
+        println!("🪙 {} is open for trade. Type 'list' to see the wares.", colored_text(shop, MessageType::Action));
+    }
+
+    if !room.exits.is_empty() {
+        let exits = room.exits.iter()
+            .map(|e| {
+                let exit = colored_text(e, MessageType::Action).to_string();
+                if room.locked_exits.contains(e) { format!("{} 🔒", exit) } else { exit }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!("Exits: {}", exits);
+    }
+
+    if let Some(enemy_name) = &room.enemy {
+        println!("⚠️ {}", colored_text(&format!("You sense danger nearby... ({})", enemy_name), MessageType::Enemy));
+    }
+}
+
+fn print_shop(shop: &str, gold: u32, wares: &[(String, u32, Option<u32>)]) {
+    println!("{}", colored_text(&format!("=== {} ===", shop), MessageType::Info));
+    if wares.is_empty() {
+        println!("Nothing left for sale.");
+    }
+    for (item, price, left) in wares {
+        let item = colored_text(item, MessageType::Item);
+        match left {
+            Some(left) => println!(" - {}: {} gold ({} left)", item, price, left),
+            None => println!(" - {}: {} gold", item, price),
+        }
+    }
+    println!("You have {} gold.", gold);
+}
+
+fn print_item(item: &Item) {
+    println!("🔍 {}", colored_text(&item.name, MessageType::Item));
+    if !item.description.is_empty() {
+        println!("{}", item.description);
+    }
+    if let Some(flavor) = &item.flavor {
+        println!("{}", colored_text(&format!("\"{}\"", flavor), MessageType::Info));
+    }
+
+    match item.power {
+        Some(power) => println!("Type: {:?} | Power: {}", item.item_type, power),
+        None => println!("Type: {:?}", item.item_type),
+    }
+    if item.value > 0 {
+        println!("Value: {} gold", item.value);
+    }
+}
+
+pub fn print_help() {
+    println!("📝 Available Commands:");
+    println!("  help             - Show this help message");
+    println!("  status           - Show your current player status");
+    println!("  go <direction>   - Move in a direction (north, south, east, west)");
+    println!("  look             - Look around the current room");
+    println!("  examine <thing>  - Take a closer look at an item, enemy or feature");
+    println!("  take [n|all] <item> - Pick up an item (the whole stack by default)");
+    println!("  use [n|all] <item>  - Use an item from your inventory");
+    println!("  drop [n|all] <item> - Leave an item in the room");
+    println!("  open <container> - Open a chest, bag or box");
+    println!("  search [container] - Search the room for hidden things, or look inside a container");
+    println!("  take <item> from <container> - Take an item out of an open container");
+    println!("  put <item> in <container> - Store an item in an open container");
+    println!("  inventory / inv  - Show your inventory");
+    println!("  journal          - Show your quests and objectives");
+    println!("  talk <person>    - Start a conversation; answer with a number");
+    println!("  bye              - End the current conversation");
+    println!("  recipes          - List the recipes you know");
+    println!("  craft <recipe>   - Make something from a known recipe");
+    println!("  list             - See what the shop here sells");
+    println!("  buy [n] <item>   - Buy an item from the shop here");
+    println!("  sell [n|all] <item> - Sell an item to the shop here");
+    println!("  equip <item>     - Equip a weapon, armor or trinket");
+    println!("  unequip <slot>   - Take off your weapon, armor or trinket");
+    println!("  save [slot]      - Save your game progress (default slot: quicksave)");
+    println!("  load [slot]      - Load a saved game");
+    println!("  saves            - List your save slots");
+    println!("  quit / exit      - Quit the game");
+    println!("  In combat: attack / heal / defend / run");
+}
+
+pub fn print_status(player: &Player) {
+    println!("{}", colored_text("=== Player Status ===", MessageType::Info));
+    println!("Name: {}", player.name);
+    println!("Level: {} | XP: {}/{}", player.level, player.xp, player.xp_to_next_level());
+    println!("Health: {} ❤️", player.health);
+    println!("Attack: {} ⚔️", player.attack_damage());
+    println!("Defense: {} 🛡️", player.total_defense());
+    println!("Gold: {} 🪙", player.gold);
+    for slot in EquipSlot::ALL {
+        let item = player.equipment.get(slot).map_or("(none)", |i| i.name.as_str());
+        println!("{:<8} {}", format!("{}:", capitalize(slot.name())), item);
+    }
+    if !player.effects.is_empty() {
+        let effects = effects::summary(&player.effects)
+            .into_iter()
+            .map(|(kind, turns)| format!("{} ({})", kind.name(), turns_text(turns)))
+            .join(", ");
+        println!("Effects: {}", effects);
+    }
+    println!("Current Room: {}", player.current_room);
+
+    let inventory = if player.inventory.is_empty() {
+        "Empty".to_string()
+    } else {
+        item_list(&player.inventory)
+    };
+    println!("Inventory: [{}]", inventory);
+}
+
+fn print_journal(quests: &[QuestView]) {
+    if quests.is_empty() {
+        println!("Your journal is empty.");
+        return;
+    }
+
+    println!("{}", colored_text("=== Journal ===", MessageType::Info));
+    for quest in quests {
+        let (mark, msg_type) = if quest.completed { ("✔", MessageType::Success) } else { ("•", MessageType::Action) };
+        println!("{} {}", mark, colored_text(&quest.title, msg_type));
+        if !quest.completed && !quest.description.is_empty() {
+            println!("  {}", quest.description);
+        }
+        for (objective, progress, needed) in &quest.objectives {
+            let check = if progress >= needed { "x" } else { " " };
+            println!("  [{}] {} ({}/{})", check, objective, progress, needed);
+        }
+    }
+}
+
+fn print_recipes(recipes: &[RecipeView]) {
+    if recipes.is_empty() {
+        println!("You don't know any recipes.");
+        return;
+    }
+
+    println!("{}", colored_text("=== Recipes ===", MessageType::Info));
+    for recipe in recipes {
+        let inputs = recipe.inputs.iter().map(|(item, count)| with_count(item, *count)).join(" + ");
+        let output = with_count(&recipe.output.0, recipe.output.1);
+        println!("• {}: {} → {}", colored_text(&recipe.name, MessageType::Action), inputs, output);
+        if let Some(tool) = &recipe.tool {
+            println!("  Needs: {}", tool);
+        }
+        if let Some(room) = &recipe.room {
+            println!("  Made at: {}", room);
+        }
+    }
+}
+
+fn print_saves(saves: &[(String, SaveMeta)]) {
+    if saves.is_empty() {
+        println!("No saved games yet.");
+        return;
+    }
+
+    println!("{}", colored_text("=== Saved Games ===", MessageType::Info));
+    for (slot, meta) in saves {
+        // Saves from before the listing existed never recorded when or for how long
+        let (saved_at, playtime) = match DateTime::from_timestamp(meta.saved_at as i64, 0) {
+            Some(t) if meta.saved_at > 0 => (
+                t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string(),
+                format!("{}h {:02}m", meta.playtime_secs / 3600, meta.playtime_secs / 60 % 60),
+            ),
+            _ => ("unknown".to_string(), "unknown".to_string()),
+        };
+        let place = if meta.level.is_empty() {
+            meta.room.clone()
+        } else {
+            format!("{} @ {}", meta.level, meta.room)
+        };
+
+        println!(
+            "{} | {} | {} | Lv {} | played {}",
+            colored_text(slot, MessageType::Item),
+            saved_at,
+            place,
+            meta.player_level,
+            playtime
+        );
+    }
+}
+
+pub fn print_validation_report(issues: &[Issue]) {
+    for issue in issues {
+        let (label, msg_type) = match issue.severity {
+            Severity::Error => ("error", MessageType::Error),
+            Severity::Warning => ("warning", MessageType::Warning),
+        };
+        println!("{} [{}] {}", colored_text(label, msg_type), issue.level, issue.message);
+    }
+
+    let errors = issues.iter().filter(|i| i.severity == Severity::Error).count();
+    let warnings = issues.len() - errors;
+    let summary = format!("{} error(s), {} warning(s)", errors, warnings);
+    let msg_type = if errors == 0 { MessageType::Success } else { MessageType::Error };
+    println!("{}", colored_text(&summary, msg_type));
+}
+
+// Groups items by name: "Healing Herb x3, Map"
+fn item_list(items: &[Item]) -> String {
+    let mut grouped: Vec<(&str, u32)> = Vec::new();
+    for item in items {
+        match grouped.iter_mut().find(|(name, _)| *name == item.name) {
+            Some((_, count)) => *count += item.quantity,
+            None => grouped.push((&item.name, item.quantity)),
+        }
+    }
+    grouped.into_iter().map(|(name, count)| with_count(name, count)).join(", ")
+}
+
+fn effect_tags(effects: &[(EffectKind, u32)]) -> String {
+    if effects.is_empty() {
+        return String::new();
+    }
+    let tags = effects.iter().map(|(kind, turns)| format!("{} {}", kind.name(), turns)).join(", ");
+    format!(" [{}]", tags)
+}
+
+// Effect messages name the player as "You" and an enemy as "The <name>"
+fn subject(target: &Option<String>) -> String {
+    match target {
+        None => "You".to_string(),
+        Some(enemy) => format!("The {}", enemy),
+    }
+}
+
+fn turns_text(turns: u32) -> String {
+    if turns == 1 { "1 turn".to_string() } else { format!("{} turns", turns) }
+}
+
+fn be_verb(target: &Option<String>) -> &'static str {
+    if target.is_none() { "are" } else { "is" }
+}
+
+fn lose_verb(target: &Option<String>) -> &'static str {
+    if target.is_none() { "lose" } else { "loses" }
+}
+
+fn effect_cause(effect: EffectKind) -> &'static str {
+    match effect {
+        EffectKind::Poison => "poison",
+        EffectKind::Burn => "the flames",
+        EffectKind::Stun | EffectKind::Regen => "its effects",
+    }
+}
+
+fn effect_color(effect: EffectKind) -> MessageType {
+    match effect {
+        EffectKind::Regen => MessageType::Success,
+        _ => MessageType::Warning,
+    }
+}
+
+fn with_count(name: &str, count: u32) -> String {
+    if count > 1 { format!("{} x{}", name, count) } else { name.to_string() }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+// === LEVEL TRANSITION BANNER ===
+
+pub fn print_transition_banner(title: &str) {
+    let padding = 6;
+    let inner_width = title.len() + padding * 2;
+    let border_top = format!("╔{}╗", "═".repeat(inner_width));
+    let border_bottom = format!("╚{}╝", "═".repeat(inner_width));
+
+    let line = format!("{:^width$}", format!("☽✧  {}  ✧☾", title), width = inner_width + 2);
+
+    println!();
+    println!("{}", colored_text(&border_top, MessageType::Info));
+    println!("{}", colored_text(&line, MessageType::Info));
+    println!("{}", colored_text(&border_bottom, MessageType::Info));
+    println!();
+}
+
+fn print_death_screen() {
+    println!("\n💀 You have been defeated!\n");
+    println!(
+"⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢀⡀⠀
+⠀⠀⠀⠀⠀⠀⠀⠀⢀⣀⠀⠀⠀⣶⡆⠀⣰⣿⠇⣾⡿⠛⠉⠁
+⠀⣠⣴⠾⠿⠿⠀⢀⣾⣿⣆⣀⣸⣿⣷⣾⣿⡿⢸⣿⠟⢓⠀⠀
+⣴⡟⠁⣀⣠⣤⠀⣼⣿⠾⣿⣻⣿⠃⠙⢫⣿⠃⣿⡿⠟⠛⠁⠀
+⢿⣝⣻⣿⡿⠋⠾⠟⠁⠀⠹⠟⠛⠀⠀⠈⠉⠀⠉⠀⠀⠀⠀⠀
+⠀⠉⠉⠁⠀⠀⠀⠀⠀⠀⠀⠀⠀⡀⠀⠀⣀⢀⣠⣤⣴⣤⣄⠀
+⠀⠀⠀⠀⣀⣤⣤⢶⣤⠀⠀⢀⣴⢃⣿⠟⠋⢹⣿⣣⣴⡿⠋⠀
+⠀⠀⣰⣾⠟⠉⣿⡜⣿⡆⣴⡿⠁⣼⡿⠛⢃⣾⡿⠋⢻⣇⠀⠀
+⠀⠐⣿⡁⢀⣠⣿⡇⢹⣿⡿⠁⢠⣿⠷⠟⠻⠟⠀⠀⠈⠛⠀⠀
+⠀⠀⠙⠻⠿⠟⠋⠀⠀⠙⠁⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀"
+    );
+    println!("\nGame Over. Thanks for playing Adventurer!\nAnother shall be sent to complete what you have failed in.");
+    println!("{}", colored_text("(Type 'load' to return to your last save, or 'quit'.)", MessageType::Info));
+}
@@ -1,38 +1,399 @@
 use serde::{Serialize, Deserialize};
-use std::fs::{self, File};
-use std::io::{Read, Write};
-use std::error::Error;
-use crate::{player::Player, world::World};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::{combat::CombatState, player::Player, world::World};
+use crate::items::{self, Item};
+use crate::error::{GameError, GameResult};
+
+/// Save layout written by this build. Bump it and append a migration
+/// whenever a change to the saved structs would break older saves.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Upgrades a save by exactly one format version
+type Migration = fn(&mut Value);
+
+/// `MIGRATIONS[n]` turns a version `n` save into version `n + 1`
+const MIGRATIONS: [Migration; FORMAT_VERSION as usize] = [migrate_v0_to_v1, migrate_v1_to_v2];
+
+/// Slot used when `save` / `load` are given no name
+pub const DEFAULT_SLOT: &str = "quicksave";
+
+/// Summary shown in the `saves` listing
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SaveMeta {
+    /// Seconds since the Unix epoch
+    pub saved_at: u64,
+    pub level: String,
+    pub room: String,
+    pub player_level: i32,
+    pub playtime_secs: u64,
+}
+
+/// Where the game's random stream stands, so a loaded save replays like the original
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct RngState {
+    pub seed: u64,
+    /// Words drawn from the stream so far
+    pub word_pos: u64,
+}
+
+impl RngState {
+    pub fn capture(seed: u64, rng: &ChaCha12Rng) -> Self {
+        Self { seed, word_pos: rng.get_word_pos() as u64 }
+    }
+
+    pub fn restore(&self) -> ChaCha12Rng {
+        let mut rng = ChaCha12Rng::seed_from_u64(self.seed);
+        rng.set_word_pos(self.word_pos as u128);
+        rng
+    }
+}
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SaveData {
+    pub format_version: u32,
+    #[serde(default)]
+    pub meta: SaveMeta,
     pub player: Player,
     pub world: World,
+    #[serde(default)]
+    pub combat: Option<CombatState>,
+    /// Missing from saves written before the stream was saved
+    #[serde(default)]
+    pub rng: Option<RngState>,
+}
+
+/// Only the metadata of a save file, for listing slots without loading them
+#[derive(Deserialize)]
+struct SaveHeader {
+    #[serde(default)]
+    meta: SaveMeta,
+}
+
+/// Per-user directory that holds save slots
+pub fn default_save_dir() -> PathBuf {
+    dirs::data_dir()
+        .map(|dir| dir.join("shards_of_aether"))
+        .unwrap_or_default()
+        .join("saves")
+}
+
+/// File backing a slot; `None` if the name isn't a plain word
+pub fn slot_path(dir: &Path, slot: &str) -> Option<PathBuf> {
+    let valid = !slot.is_empty()
+        && slot.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| dir.join(format!("{}.json", slot)))
 }
 
-pub fn save_game(player: &Player, world: &World, path: &str) -> Result<(), Box<dyn Error>> {
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn save_game(
+    player: &Player,
+    world: &World,
+    combat: Option<&CombatState>,
+    meta: SaveMeta,
+    rng: RngState,
+    path: &Path,
+) -> GameResult<()> {
     let data = SaveData {
+        format_version: FORMAT_VERSION,
+        meta,
         player: player.clone(),
         world: world.clone(),
+        combat: combat.cloned(),
+        rng: Some(rng),
     };
-    let json = serde_json::to_string_pretty(&data)?;
-    let mut file = File::create(path)?;
-    file.write_all(json.as_bytes())?;
-    Ok(())
+    let json = serde_json::to_string_pretty(&data).map_err(|e| GameError::parse(path, e))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| GameError::io(dir, e))?;
+    }
+    fs::write(path, json).map_err(|e| GameError::io(path, e))
 }
 
-pub fn load_game(player: &mut Player, world: &mut World, path: &str) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(path)?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
-    let data: SaveData = serde_json::from_str(&contents)?;
+pub fn load_game(
+    player: &mut Player,
+    world: &mut World,
+    combat: &mut Option<CombatState>,
+    path: &Path,
+) -> GameResult<(SaveMeta, Option<RngState>)> {
+    let raw: Value = read_json(path)?;
+    let data: SaveData = serde_json::from_value(migrate(raw)?).map_err(|e| GameError::parse(path, e))?;
     *player = data.player;
     *world = data.world;
-    Ok(())
+    *combat = data.combat;
+    Ok((data.meta, data.rng))
 }
 
-pub fn load_world(path: &str) -> Result<World, Box<dyn Error>> {
-    let data = fs::read_to_string(path)?;
-    let world: World = serde_json::from_str(&data)?;
-    Ok(world)
+/// Brings raw save JSON up to `FORMAT_VERSION`, one step at a time
+pub fn migrate(mut data: Value) -> GameResult<Value> {
+    let Some(object) = data.as_object_mut() else {
+        return Err(GameError::InvalidSave("not a JSON object".to_string()));
+    };
+
+    // Saves written before versioning have no field at all
+    let version = object.get("format_version").and_then(Value::as_u64).unwrap_or(0);
+    if version > FORMAT_VERSION as u64 {
+        return Err(GameError::UnsupportedSaveVersion { found: version, supported: FORMAT_VERSION });
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(&mut data);
+    }
+    data["format_version"] = FORMAT_VERSION.into();
+    Ok(data)
+}
+
+// v0 → v1: unversioned saves predate the defense stat and the listing
+// metadata, and still carry the "<level>_loaded" flags the old hardcoded
+// level progression relied on
+fn migrate_v0_to_v1(data: &mut Value) {
+    let Some(player) = data.get_mut("player").and_then(Value::as_object_mut) else { return };
+
+    // What the listing needs can be read off the player; the time saved and
+    // playtime were never recorded and stay zero
+    let meta = SaveMeta {
+        room: player.get("current_room").and_then(Value::as_str).unwrap_or_default().to_string(),
+        player_level: player.get("level").and_then(Value::as_i64).unwrap_or(1) as i32,
+        ..SaveMeta::default()
+    };
+
+    player.entry("defense").or_insert(Value::from(0));
+    if let Some(flags) = player.get_mut("flags").and_then(Value::as_array_mut) {
+        flags.retain(|flag| !flag.as_str().is_some_and(|f| f.ends_with("_loaded")));
+    }
+    if data.get("meta").is_none() {
+        data["meta"] = serde_json::to_value(meta).unwrap_or_default();
+    }
+}
+
+// v1 → v2: items saved before stacking, weights, prices and effects lack
+// those fields, so they would never stack again. Fill them in from the item
+// registry by name and merge the duplicates that piled up meanwhile.
+fn migrate_v1_to_v2(data: &mut Value) {
+    if let Some(player) = data.get_mut("player") {
+        if let Some(inventory) = player.get_mut("inventory") {
+            upgrade_item_list(inventory);
+        }
+        if let Some(equipment) = player.get_mut("equipment").and_then(Value::as_object_mut) {
+            equipment.values_mut().for_each(upgrade_item);
+        }
+    }
+
+    if let Some(rooms) = data.pointer_mut("/world/rooms").and_then(Value::as_object_mut) {
+        for room in rooms.values_mut() {
+            if let Some(list) = room.get_mut("items") {
+                upgrade_item_list(list);
+            }
+            if let Some(containers) = room.get_mut("containers").and_then(Value::as_array_mut) {
+                for list in containers.iter_mut().filter_map(|c| c.get_mut("items")) {
+                    upgrade_item_list(list);
+                }
+            }
+        }
+    }
+
+    if let Some(loot) = data.pointer_mut("/combat/enemy/loot").and_then(Value::as_array_mut) {
+        for entry in loot.iter_mut() {
+            if let Some(item) = entry.get_mut("item") {
+                upgrade_item(item);
+            }
+        }
+    }
+}
+
+// Adds any field the saved item lacks from its registry definition
+fn upgrade_item(item: &mut Value) {
+    let Some(fields) = item.as_object_mut() else { return };
+    let Some(name) = fields.get("name").and_then(Value::as_str) else { return };
+    let Some(Value::Object(definition)) = items::get_item_by_name(name).and_then(|i| serde_json::to_value(i).ok()) else {
+        return;
+    };
+
+    for (key, value) in definition {
+        fields.entry(key).or_insert(value);
+    }
+}
+
+fn upgrade_item_list(list: &mut Value) {
+    let Some(entries) = list.as_array_mut() else { return };
+    entries.iter_mut().for_each(upgrade_item);
+
+    let Ok(saved) = serde_json::from_value::<Vec<Item>>(list.clone()) else { return };
+    let mut stacked = Vec::new();
+    for item in saved {
+        items::add_to_stack(&mut stacked, item);
+    }
+    if let Ok(value) = serde_json::to_value(stacked) {
+        *list = value;
+    }
+}
+
+/// Every readable slot in `dir`, sorted by name
+pub fn list_saves(dir: &Path) -> Vec<(String, SaveMeta)> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+
+    let mut saves: Vec<(String, SaveMeta)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let slot = path.file_stem()?.to_string_lossy().into_owned();
+            let raw: Value = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+            let header: SaveHeader = serde_json::from_value(migrate(raw).ok()?).ok()?;
+            Some((slot, header.meta))
+        })
+        .collect();
+
+    saves.sort_by(|a, b| a.0.cmp(&b.0));
+    saves
+}
+
+pub fn load_world(path: impl AsRef<Path>) -> GameResult<World> {
+    read_asset(path)
+}
+
+/// Reads an asset file, resolving the item ids it refers to.
+/// The item registry must already be loaded.
+pub(crate) fn read_asset<T: DeserializeOwned>(path: impl AsRef<Path>) -> GameResult<T> {
+    items::resolving_ids(|| read_json(path))
+}
+
+/// Reads and deserializes a JSON file, naming the file in any error
+pub(crate) fn read_json<T: DeserializeOwned>(path: impl AsRef<Path>) -> GameResult<T> {
+    let path = path.as_ref();
+    let data = fs::read_to_string(path).map_err(|e| GameError::io(path, e))?;
+    serde_json::from_str(&data).map_err(|e| GameError::parse(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn slot_names_are_plain_words() {
+        let dir = Path::new("saves");
+        assert_eq!(slot_path(dir, "run-2_b"), Some(dir.join("run-2_b.json")));
+        for bad in ["", "../escape", "a b", "slot.json"] {
+            assert_eq!(slot_path(dir, bad), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn lists_saved_slots_with_their_meta() {
+        let dir = std::env::temp_dir().join(format!("shards_of_aether_saves_{}", std::process::id()));
+        let world: World = serde_json::from_value(json!({ "rooms": {} })).unwrap();
+        let rng = RngState { seed: 1, word_pos: 0 };
+        for (slot, room) in [("beta", "gate"), ("alpha", "hall")] {
+            let meta = SaveMeta { room: room.to_string(), saved_at: 10, ..SaveMeta::default() };
+            save_game(&Player::new(), &world, None, meta, rng, &slot_path(&dir, slot).unwrap()).unwrap();
+        }
+        fs::write(dir.join("notes.txt"), "not a save").unwrap();
+
+        let saves = list_saves(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let listed: Vec<(&str, &str)> = saves.iter().map(|(slot, meta)| (slot.as_str(), meta.room.as_str())).collect();
+        assert_eq!(listed, [("alpha", "hall"), ("beta", "gate")]);
+    }
+
+    #[test]
+    fn restored_rng_continues_the_same_stream() {
+        use rand::Rng;
+
+        let mut rng = ChaCha12Rng::seed_from_u64(42);
+        let _: [u32; 5] = rng.gen();
+        let mut restored = RngState::capture(42, &rng).restore();
+
+        let expected: [u64; 4] = rng.gen();
+        assert_eq!(restored.gen::<[u64; 4]>(), expected);
+    }
+
+    #[test]
+    fn migrates_unversioned_saves() {
+        let raw = json!({
+            "player": {
+                "level": 3,
+                "current_room": "sacred_altar",
+                "flags": ["level1_loaded", "altar_lit"]
+            }
+        });
+
+        let data = migrate(raw).unwrap();
+        assert_eq!(data["format_version"], FORMAT_VERSION);
+        assert_eq!(data["player"]["defense"], 0);
+        assert_eq!(data["player"]["flags"], json!(["altar_lit"]));
+        assert_eq!(data["meta"]["room"], "sacred_altar");
+        assert_eq!(data["meta"]["player_level"], 3);
+        assert_eq!(data["meta"]["saved_at"], 0);
+    }
+
+    #[test]
+    fn keeps_existing_meta() {
+        let raw = json!({
+            "meta": { "saved_at": 5, "level": "Forest", "room": "hall", "player_level": 2, "playtime_secs": 60 },
+            "player": { "level": 4, "current_room": "sacred_altar" }
+        });
+
+        let data = migrate(raw).unwrap();
+        assert_eq!(data["meta"]["room"], "hall");
+        assert_eq!(data["meta"]["player_level"], 2);
+    }
+
+    #[test]
+    fn fills_item_fields_from_the_registry() {
+        let herb: Item = serde_json::from_value(json!({
+            "name": "Migration Test Herb",
+            "item_type": "Healing",
+            "power": 20,
+            "usable_on": null,
+            "stackable": true,
+            "value": 4
+        }))
+        .unwrap();
+        // The registry is shared by every test, so only add an entry no other test uses
+        items::ITEM_LIST.write().unwrap().insert("migration_test_herb".to_string(), herb);
+
+        let old_herb = json!({ "name": "Migration Test Herb", "item_type": "Healing", "power": 20, "usable_on": null });
+        let raw = json!({
+            "format_version": 1,
+            "player": { "inventory": [old_herb.clone(), old_herb.clone()] },
+            "world": { "rooms": { "hall": { "items": [old_herb] } } }
+        });
+
+        let data = migrate(raw).unwrap();
+        let inventory = data["player"]["inventory"].as_array().unwrap();
+        assert_eq!(inventory.len(), 1);
+        assert_eq!(inventory[0]["quantity"], 2);
+        assert_eq!(inventory[0]["stackable"], true);
+        assert_eq!(data["world"]["rooms"]["hall"]["items"][0]["value"], 4);
+    }
+
+    #[test]
+    fn rejects_future_versions() {
+        let raw = json!({ "format_version": FORMAT_VERSION + 1, "player": {} });
+
+        match migrate(raw) {
+            Err(GameError::UnsupportedSaveVersion { found, supported }) => {
+                assert_eq!(found, FORMAT_VERSION as u64 + 1);
+                assert_eq!(supported, FORMAT_VERSION);
+            }
+            other => panic!("expected UnsupportedSaveVersion, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn rejects_non_objects() {
+        assert!(matches!(migrate(json!([1, 2])), Err(GameError::InvalidSave(_))));
+    }
 }
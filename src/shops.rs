@@ -0,0 +1,252 @@
+use serde::{Serialize, Deserialize};
+use crate::events::GameEvent;
+use crate::items::{self, Item, ItemType};
+use crate::player::{InventoryLimits, Player};
+use crate::world::World;
+
+/// A merchant's wares, kept in the room so sales are remembered in saves
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Shop {
+    /// Merchant or shop name shown to the player
+    pub name: String,
+    #[serde(default)]
+    pub stock: Vec<StockEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StockEntry {
+    #[serde(deserialize_with = "items::deserialize_item")]
+    pub item: Item,
+    /// Price of one unit; defaults to the item's value
+    #[serde(default)]
+    pub price: Option<u32>,
+    /// Units left; unlimited when left out
+    #[serde(default)]
+    pub quantity: Option<u32>,
+}
+
+impl StockEntry {
+    pub fn price(&self) -> u32 {
+        self.price.unwrap_or(self.item.value)
+    }
+}
+
+/// Most units of a free ware with unlimited stock handed out by one purchase
+const MAX_FREE_UNITS: u32 = 20;
+
+/// What a merchant pays for one unit: half its value
+pub fn sell_price(item: &Item) -> u32 {
+    item.value / 2
+}
+
+fn shop_here<'a>(player: &Player, world: &'a mut World) -> Option<&'a mut Shop> {
+    world.rooms.get_mut(&player.current_room)?.shop.as_mut()
+}
+
+/// Shows what the shop in the current room sells
+pub fn list(player: &Player, world: &mut World, events: &mut Vec<GameEvent>) {
+    let Some(shop) = shop_here(player, world) else {
+        events.push(GameEvent::NoShop);
+        return;
+    };
+
+    events.push(GameEvent::ShopList {
+        shop: shop.name.clone(),
+        gold: player.gold,
+        wares: shop
+            .stock
+            .iter()
+            .map(|entry| (entry.item.name.clone(), entry.price(), entry.quantity))
+            .collect(),
+    });
+}
+
+/// Buys `count` units (one by default), stopping early when gold or carrying space runs out
+pub fn buy(
+    item_name: &str,
+    count: Option<u32>,
+    limits: &InventoryLimits,
+    player: &mut Player,
+    world: &mut World,
+    events: &mut Vec<GameEvent>,
+) {
+    let Some(shop) = shop_here(player, world) else {
+        events.push(GameEvent::NoShop);
+        return;
+    };
+    let Some(pos) = shop.stock.iter().position(|e| e.item.name.eq_ignore_ascii_case(item_name)) else {
+        events.push(GameEvent::NotForSale(item_name.to_string()));
+        return;
+    };
+
+    let entry = &mut shop.stock[pos];
+    let name = entry.item.name.clone();
+    let price = entry.price();
+    // Unlimited stock is capped by what the player can afford, and free wares by a fixed amount
+    let affordable = player.gold.checked_div(price).map_or(MAX_FREE_UNITS, |units| units.max(1));
+    let wanted = count.unwrap_or(1).min(entry.quantity.unwrap_or(affordable));
+
+    let mut bought = 0;
+    while bought < wanted {
+        if player.gold < price {
+            events.push(GameEvent::NotEnoughGold { item: name.clone(), price });
+            break;
+        }
+        let unit = Item { quantity: 1, ..entry.item.clone() };
+        if !player.can_carry(&unit, limits) {
+            events.push(GameEvent::InventoryFull(name.clone()));
+            break;
+        }
+        player.gold -= price;
+        player.add_item(unit);
+        bought += 1;
+    }
+
+    if let Some(left) = entry.quantity.as_mut() {
+        *left -= bought;
+        if *left == 0 {
+            shop.stock.remove(pos);
+        }
+    }
+    if bought > 0 {
+        events.push(GameEvent::Bought { item: name, count: bought, cost: price * bought });
+    }
+}
+
+/// Sells `count` units (one by default) of an inventory item to the shop here
+pub fn sell(item_name: &str, count: Option<u32>, player: &mut Player, world: &mut World, events: &mut Vec<GameEvent>) {
+    let Some(pos) = items::find_item(&player.inventory, item_name) else {
+        events.push(GameEvent::NotInInventory(item_name.to_string()));
+        return;
+    };
+    let price = sell_price(&player.inventory[pos]);
+    let item_type = player.inventory[pos].item_type.clone();
+    let Some(shop) = shop_here(player, world) else {
+        events.push(GameEvent::NoShop);
+        return;
+    };
+    if matches!(item_type, ItemType::Quest) || price == 0 {
+        events.push(GameEvent::CannotSell(player.inventory[pos].name.clone()));
+        return;
+    }
+
+    let sold = items::take_from_stack(&mut player.inventory, pos, count.unwrap_or(1));
+    let gold = price * sold.quantity;
+    player.gold += gold;
+    events.push(GameEvent::Sold { item: sold.name.clone(), count: sold.quantity, gold });
+
+    // The merchant puts it up for sale; unlimited stock has no room to grow
+    match shop.stock.iter_mut().find(|e| e.item.name.eq_ignore_ascii_case(&sold.name)) {
+        Some(entry) => {
+            if let Some(left) = entry.quantity.as_mut() {
+                *left += sold.quantity;
+            }
+        }
+        None => shop.stock.push(StockEntry {
+            quantity: Some(sold.quantity),
+            item: Item { quantity: 1, ..sold },
+            price: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn market(stock: Value) -> World {
+        serde_json::from_value(json!({
+            "rooms": { "market": { "id": "market", "description": "", "shop": { "name": "Stall", "stock": stock } } }
+        }))
+        .unwrap()
+    }
+
+    fn ware(name: &str, value: u32) -> Value {
+        json!({ "name": name, "item_type": "Healing", "power": 5, "usable_on": null, "stackable": true, "value": value })
+    }
+
+    fn shopper(gold: u32) -> Player {
+        Player { current_room: "market".to_string(), gold, ..Player::new() }
+    }
+
+    fn carried(player: &Player, name: &str) -> u32 {
+        items::count_of(&player.inventory, name)
+    }
+
+    #[test]
+    fn free_unlimited_wares_stop_at_a_fixed_amount() {
+        let mut world = market(json!([{ "item": ware("Water Flask", 0) }]));
+        let mut player = shopper(0);
+        let mut events = Vec::new();
+
+        buy("water flask", Some(u32::MAX), &InventoryLimits::default(), &mut player, &mut world, &mut events);
+        assert_eq!(carried(&player, "Water Flask"), MAX_FREE_UNITS);
+
+        // Carry limits still stop it sooner
+        let mut player = shopper(0);
+        let limits = InventoryLimits { max_slots: None, max_weight: Some(3) };
+        let mut flask = ware("Water Flask", 0);
+        flask["weight"] = json!(1);
+        let mut world = market(json!([{ "item": flask }]));
+        buy("water flask", Some(u32::MAX), &limits, &mut player, &mut world, &mut events);
+        assert_eq!(carried(&player, "Water Flask"), 3);
+    }
+
+    #[test]
+    fn capped_stock_sells_out() {
+        let mut world = market(json!([{ "item": ware("Healing Herb", 4), "quantity": 2 }]));
+        let mut player = shopper(100);
+        let mut events = Vec::new();
+
+        buy("Healing Herb", Some(u32::MAX), &InventoryLimits::default(), &mut player, &mut world, &mut events);
+
+        assert_eq!(carried(&player, "Healing Herb"), 2);
+        assert_eq!(player.gold, 92);
+        assert!(world.rooms["market"].shop.as_ref().unwrap().stock.is_empty());
+    }
+
+    #[test]
+    fn buying_stops_when_the_gold_runs_out() {
+        let mut world = market(json!([{ "item": ware("Healing Herb", 10), "quantity": 5 }]));
+        let mut player = shopper(25);
+        let mut events = Vec::new();
+
+        buy("Healing Herb", Some(3), &InventoryLimits::default(), &mut player, &mut world, &mut events);
+
+        assert_eq!(carried(&player, "Healing Herb"), 2);
+        assert_eq!(player.gold, 5);
+        assert_eq!(world.rooms["market"].shop.as_ref().unwrap().stock[0].quantity, Some(3));
+        assert!(matches!(events[0], GameEvent::NotEnoughGold { price: 10, .. }));
+        assert!(matches!(events[1], GameEvent::Bought { count: 2, cost: 20, .. }));
+    }
+
+    #[test]
+    fn buying_all_of_an_unlimited_ware_spends_what_the_player_has() {
+        let mut world = market(json!([{ "item": ware("Healing Herb", 10) }]));
+        let mut player = shopper(25);
+        let mut events = Vec::new();
+
+        buy("Healing Herb", Some(u32::MAX), &InventoryLimits::default(), &mut player, &mut world, &mut events);
+
+        assert_eq!(carried(&player, "Healing Herb"), 2);
+        assert!(matches!(&events[..], [GameEvent::Bought { count: 2, .. }]));
+    }
+
+    #[test]
+    fn sold_items_join_the_stock_at_half_value() {
+        let mut world = market(json!([]));
+        let mut player = shopper(0);
+        let mut herbs = ware("Healing Herb", 10);
+        herbs["quantity"] = json!(3);
+        player.add_item(serde_json::from_value(herbs).unwrap());
+        let mut events = Vec::new();
+
+        sell("healing herb", Some(2), &mut player, &mut world, &mut events);
+
+        assert_eq!(player.gold, 10);
+        assert_eq!(carried(&player, "Healing Herb"), 1);
+        let stock = &world.rooms["market"].shop.as_ref().unwrap().stock;
+        assert_eq!((stock[0].item.quantity, stock[0].quantity), (1, Some(2)));
+    }
+}
@@ -0,0 +1,306 @@
+use serde::{Serialize, Deserialize};
+use crate::crafting;
+use crate::effects::{self, StatusEffect};
+use crate::events::GameEvent;
+use crate::items::{self, Item};
+use crate::player::Player;
+use crate::quests;
+use crate::world::{self, World};
+
+/// What a trigger reacts to
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerEvent {
+    OnEnter,
+    OnFirstEnter,
+    OnTake,
+    OnUseItem,
+    OnEnemyDefeated,
+}
+
+/// Scripted room behavior: when `on` happens and the conditions hold, run the actions
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Trigger {
+    pub on: TriggerEvent,
+    /// Item taken or used, or enemy defeated; any when left out
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub conditions: Conditions,
+    #[serde(default)]
+    pub actions: Vec<Action>,
+    /// Stops what caused the trigger, e.g. keeps an item from being taken
+    #[serde(default)]
+    pub block: bool,
+    /// Fire at most once
+    #[serde(default)]
+    pub once: bool,
+    #[serde(default)]
+    pub fired: bool,
+}
+
+/// Requirements on the player; every one listed must hold
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Conditions {
+    #[serde(default)]
+    pub flags: Vec<String>,
+    /// Flags that must not be set
+    #[serde(default)]
+    pub missing_flags: Vec<String>,
+    /// Items that must be carried
+    #[serde(default)]
+    pub items: Vec<String>,
+    #[serde(default)]
+    pub min_level: Option<i32>,
+    #[serde(default)]
+    pub min_health: Option<i32>,
+    #[serde(default)]
+    pub min_attack: Option<i32>,
+}
+
+impl Conditions {
+    pub fn met(&self, player: &Player) -> bool {
+        self.flags.iter().all(|f| player.flags.contains(f))
+            && !self.missing_flags.iter().any(|f| player.flags.contains(f))
+            && self.items.iter().all(|i| items::find_item(&player.inventory, i).is_some())
+            && self.min_level.is_none_or(|min| player.level >= min)
+            && self.min_health.is_none_or(|min| player.health >= min)
+            && self.min_attack.is_none_or(|min| player.attack_damage() >= min)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Action {
+    Print { text: String },
+    SetFlag { flag: String },
+    /// Puts an enemy in a room, the current one by default
+    SpawnEnemy {
+        enemy: String,
+        #[serde(default)]
+        room: Option<String>,
+    },
+    GiveItem {
+        #[serde(deserialize_with = "items::deserialize_item")]
+        item: Item,
+    },
+    /// Removes items the player carries, by name
+    TakeItem {
+        item: String,
+        #[serde(default = "default_count")]
+        count: u32,
+    },
+    /// Unlocks an exit, or adds one when `to` is given
+    OpenExit {
+        direction: String,
+        #[serde(default)]
+        to: Option<String>,
+        #[serde(default)]
+        room: Option<String>,
+    },
+    Teleport { room: String },
+    StartQuest { quest: String },
+    LearnRecipe { recipe: String },
+    /// Puts an effect on the player, like a blessing or a curse
+    ApplyEffect { effect: StatusEffect },
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+impl Trigger {
+    fn matches(&self, event: TriggerEvent, target: Option<&str>, player: &Player) -> bool {
+        self.on == event
+            && !(self.once && self.fired)
+            && self.target.as_ref().is_none_or(|wanted| {
+                target.is_some_and(|target| wanted.eq_ignore_ascii_case(target))
+            })
+            && self.conditions.met(player)
+    }
+}
+
+/// Runs the current room's triggers for an event.
+/// Returns true if one of them blocks whatever caused it.
+pub fn fire(
+    event: TriggerEvent,
+    target: Option<&str>,
+    player: &mut Player,
+    world: &mut World,
+    events: &mut Vec<GameEvent>,
+) -> bool {
+    let Some(room) = world.rooms.get_mut(&player.current_room) else { return false };
+
+    let mut blocked = false;
+    let mut actions = Vec::new();
+    for trigger in room.triggers.iter_mut().filter(|t| t.matches(event, target, player)) {
+        trigger.fired = true;
+        blocked |= trigger.block;
+        actions.extend(trigger.actions.iter().cloned());
+    }
+
+    for action in actions {
+        run(action, player, world, events);
+    }
+    blocked
+}
+
+pub(crate) fn run(action: Action, player: &mut Player, world: &mut World, events: &mut Vec<GameEvent>) {
+    match action {
+        Action::Print { text } => events.push(GameEvent::Narration(text)),
+
+        Action::SetFlag { flag } => {
+            if !player.flags.contains(&flag) {
+                player.flags.push(flag);
+            }
+        }
+
+        Action::SpawnEnemy { enemy, room } => {
+            let room = room.unwrap_or_else(|| player.current_room.clone());
+            if let Some(room) = world.rooms.get_mut(&room) {
+                room.enemy = Some(enemy);
+            }
+        }
+
+        Action::GiveItem { item } => {
+            events.push(GameEvent::ItemReceived { item: item.name.clone(), count: item.quantity });
+            player.add_item(item);
+        }
+
+        Action::TakeItem { item, count } => {
+            let Some(pos) = items::find_item(&player.inventory, &item) else { return };
+            let taken = items::take_from_stack(&mut player.inventory, pos, count);
+            events.push(GameEvent::ItemHandedOver { item: taken.name, count: taken.quantity });
+        }
+
+        Action::OpenExit { direction, to, room } => {
+            let room = room.unwrap_or_else(|| player.current_room.clone());
+            let Some(room) = world.rooms.get_mut(&room) else { return };
+            room.locks.remove(&direction);
+            if let Some(to) = to {
+                room.exits.insert(direction.clone(), to);
+            }
+            events.push(GameEvent::ExitOpened { direction });
+        }
+
+        Action::Teleport { room } => {
+            if !world.rooms.contains_key(&room) {
+                return;
+            }
+            player.current_room = room;
+            events.push(GameEvent::Teleported { room: player.current_room.clone() });
+            // The engine starts any fight waiting there once the turn ends
+            world::enter_room(player, world, events);
+        }
+
+        Action::StartQuest { quest } => quests::start(&quest, player, events),
+        Action::ApplyEffect { effect } => effects::apply(&mut player.effects, effect, &None, events),
+        Action::LearnRecipe { recipe } => {
+            crafting::learn(&recipe, player, events);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::InventoryLimits;
+    use serde_json::json;
+
+    fn world(rooms: serde_json::Value) -> World {
+        serde_json::from_value(json!({ "rooms": rooms })).unwrap()
+    }
+
+    fn player_in(room: &str) -> Player {
+        Player { current_room: room.to_string(), ..Player::new() }
+    }
+
+    fn narration(events: &[GameEvent]) -> Vec<&str> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                GameEvent::Narration(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn blocking_triggers_keep_items_in_place() {
+        let mut world = world(json!({
+            "shrine": {
+                "id": "shrine",
+                "description": "",
+                "items": [{ "name": "Relic", "item_type": "Utility", "power": null, "usable_on": null }],
+                "triggers": [{
+                    "on": "on_take",
+                    "target": "relic",
+                    "block": true,
+                    "actions": [{ "type": "print", "text": "The relic will not budge." }]
+                }]
+            }
+        }));
+        let mut player = player_in("shrine");
+        let mut events = Vec::new();
+
+        world::take_item("Relic", None, &InventoryLimits::default(), &mut player, &mut world, &mut events);
+
+        assert!(player.inventory.is_empty());
+        assert_eq!(world.rooms["shrine"].items.len(), 1);
+        assert_eq!(narration(&events), ["The relic will not budge."]);
+    }
+
+    #[test]
+    fn once_triggers_fire_once_and_conditions_gate_them() {
+        let mut world = world(json!({
+            "hall": {
+                "id": "hall",
+                "description": "",
+                "triggers": [
+                    { "on": "on_enter", "once": true, "actions": [{ "type": "set_flag", "flag": "greeted" }] },
+                    {
+                        "on": "on_enter",
+                        "conditions": { "flags": ["greeted"], "min_level": 2 },
+                        "actions": [{ "type": "print", "text": "Welcome back, veteran." }]
+                    }
+                ]
+            }
+        }));
+        let mut player = player_in("hall");
+        let mut events = Vec::new();
+
+        fire(TriggerEvent::OnEnter, None, &mut player, &mut world, &mut events);
+        fire(TriggerEvent::OnEnter, None, &mut player, &mut world, &mut events);
+        assert_eq!(player.flags, ["greeted"]);
+        assert!(narration(&events).is_empty());
+
+        player.level = 2;
+        fire(TriggerEvent::OnEnter, None, &mut player, &mut world, &mut events);
+        assert_eq!(narration(&events), ["Welcome back, veteran."]);
+    }
+
+    #[test]
+    fn teleporting_counts_as_entering_the_room() {
+        let mut world = world(json!({
+            "hall": { "id": "hall", "description": "" },
+            "crypt": {
+                "id": "crypt",
+                "description": "",
+                "triggers": [
+                    { "on": "on_first_enter", "actions": [{ "type": "print", "text": "Cold air greets you." }] },
+                    { "on": "on_enter", "actions": [{ "type": "set_flag", "flag": "in_crypt" }] }
+                ]
+            }
+        }));
+        let mut player = player_in("hall");
+        let mut events = Vec::new();
+
+        run(Action::Teleport { room: "crypt".to_string() }, &mut player, &mut world, &mut events);
+
+        assert_eq!(player.current_room, "crypt");
+        assert!(world.visited.contains("crypt"));
+        assert!(player.flags.contains(&"in_crypt".to_string()));
+        assert_eq!(narration(&events), ["Cold air greets you."]);
+        assert!(matches!(events[0], GameEvent::Teleported { .. }));
+    }
+}
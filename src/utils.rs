@@ -1,9 +1,10 @@
-use std::io::{self, Write};
+use std::io;
 
-pub fn get_input() -> String {
-    print!("> ");
-    io::stdout().flush().unwrap();
+/// Reads one trimmed line; `None` once stdin is closed or unreadable
+pub fn get_input() -> Option<String> {
     let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap();
-    input.trim().to_string()
+    match io::stdin().read_line(&mut input) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(input.trim().to_string()),
+    }
 }
@@ -0,0 +1,580 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::campaign::Campaign;
+use crate::crafting::Recipe;
+use crate::enemies::{AbilityEffect, Enemy};
+use crate::items::{Item, ItemType};
+use crate::npcs::Npc;
+use crate::quests::{ObjectiveKind, Quest};
+use crate::save_load::load_world;
+use crate::triggers::Action;
+use crate::world::{ExitLock, World};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// Breaks the game at runtime
+    Error,
+    /// Likely a mistake, but playable
+    Warning,
+}
+
+/// One problem found in the assets
+#[derive(Clone, Debug)]
+pub struct Issue {
+    pub severity: Severity,
+    /// Level id the issue belongs to, "campaign", "items", "enemies", "quests", "npcs" or "recipes"
+    pub level: String,
+    pub message: String,
+}
+
+/// Whether a report should fail validation: on any error, or on anything at all when strict
+pub fn fails(issues: &[Issue], strict: bool) -> bool {
+    issues.iter().any(|issue| strict || issue.severity == Severity::Error)
+}
+
+/// Loads every level in the campaign and checks it against the item, enemy, quest, NPC and recipe lists
+pub fn validate_campaign(
+    campaign: &Campaign,
+    items: &HashMap<String, Item>,
+    enemies: &HashMap<String, Enemy>,
+    quests: &HashMap<String, Quest>,
+    npcs: &HashMap<String, Npc>,
+    recipes: &HashMap<String, Recipe>,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut campaign_issue = |severity, message: String| {
+        issues.push(Issue { severity, level: "campaign".to_string(), message });
+    };
+
+    if campaign.start_index().is_none() {
+        campaign_issue(Severity::Error, format!("start level '{}' is not defined", campaign.start));
+    }
+
+    let mut seen_ids = HashSet::new();
+    for level in &campaign.levels {
+        if !seen_ids.insert(level.id.as_str()) {
+            campaign_issue(Severity::Error, format!("level id '{}' is used more than once", level.id));
+        }
+        if let Some(next) = &level.next {
+            if campaign.level_index(next).is_none() {
+                campaign_issue(
+                    Severity::Error,
+                    format!("level '{}' continues to unknown level '{}'", level.id, next),
+                );
+            }
+            if level.completion_flag.is_none() {
+                campaign_issue(
+                    Severity::Warning,
+                    format!("level '{}' has a next level but no completion_flag", level.id),
+                );
+            }
+        }
+        for quest in level.quests.iter().filter(|q| !quests.contains_key(*q)) {
+            campaign_issue(Severity::Error, format!("level '{}' starts unknown quest '{}'", level.id, quest));
+        }
+    }
+
+    let mut enemy_ids: Vec<&String> = enemies.keys().collect();
+    enemy_ids.sort();
+    for id in enemy_ids {
+        for ability in &enemies[id].abilities {
+            if let AbilityEffect::Summon { enemy } = &ability.effect {
+                if !enemies.contains_key(enemy) {
+                    issues.push(Issue {
+                        severity: Severity::Error,
+                        level: "enemies".to_string(),
+                        message: format!("enemy '{}' summons unknown enemy '{}'", id, enemy),
+                    });
+                }
+            }
+        }
+    }
+
+    let mut quest_ids: Vec<&String> = quests.keys().collect();
+    quest_ids.sort();
+    for id in quest_ids {
+        for objective in &quests[id].objectives {
+            if objective.kind == ObjectiveKind::Kill && !enemies.contains_key(&objective.target) {
+                issues.push(Issue {
+                    severity: Severity::Error,
+                    level: "quests".to_string(),
+                    message: format!("quest '{}' asks to kill unknown enemy '{}'", id, objective.target),
+                });
+            }
+        }
+    }
+
+    let mut npc_ids: Vec<&String> = npcs.keys().collect();
+    npc_ids.sort();
+    for id in npc_ids {
+        issues.extend(
+            validate_npc(id, &npcs[id], enemies, quests, recipes)
+                .into_iter()
+                .map(|message| Issue { severity: Severity::Error, level: "npcs".to_string(), message }),
+        );
+    }
+
+    // Recipes can name a room from any level
+    let mut all_rooms = HashSet::new();
+    for level in &campaign.levels {
+        match load_world(campaign.level_path(level)) {
+            Ok(world) => {
+                issues.extend(
+                    validate_world(&world, &level.start_room, enemies, quests, npcs, recipes)
+                        .into_iter()
+                        .map(|(severity, message)| Issue { severity, level: level.id.clone(), message }),
+                );
+                all_rooms.extend(world.rooms.into_keys());
+            }
+            Err(e) => issues.push(Issue {
+                severity: Severity::Error,
+                level: level.id.clone(),
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    issues.extend(
+        validate_recipes(items, recipes, &all_rooms)
+            .into_iter()
+            .map(|message| Issue { severity: Severity::Error, level: "recipes".to_string(), message }),
+    );
+    issues.extend(
+        validate_item_sources(items, enemies, quests, npcs, recipes, &all_rooms)
+            .into_iter()
+            .map(|(level, message)| Issue { severity: Severity::Error, level: level.to_string(), message }),
+    );
+
+    issues
+}
+
+/// The error for a quest item whose target room isn't in `rooms`, if any
+fn check_usable_on(item: &Item, source: &str, rooms: impl Fn(&str) -> bool) -> Option<String> {
+    if !matches!(item.item_type, ItemType::Quest) {
+        return None;
+    }
+    let target = item.usable_on.as_ref().filter(|target| !rooms(target))?;
+    Some(format!("quest item '{}' {} is usable on missing room '{}'", item.name, source, target))
+}
+
+// Items handed out outside a level can be used in a room of any level
+fn validate_item_sources(
+    items: &HashMap<String, Item>,
+    enemies: &HashMap<String, Enemy>,
+    quests: &HashMap<String, Quest>,
+    npcs: &HashMap<String, Npc>,
+    recipes: &HashMap<String, Recipe>,
+    rooms: &HashSet<String>,
+) -> Vec<(&'static str, String)> {
+    let mut issues = Vec::new();
+    let known_room = |id: &str| rooms.contains(id);
+
+    for id in sorted_keys(items) {
+        let source = format!("'{}' in the registry", id);
+        issues.extend(check_usable_on(&items[id], &source, known_room).map(|m| ("items", m)));
+    }
+    for id in sorted_keys(enemies) {
+        let source = format!("dropped by '{}'", id);
+        for item in enemies[id].loot.iter().filter_map(|entry| entry.item.as_ref()) {
+            issues.extend(check_usable_on(item, &source, known_room).map(|m| ("enemies", m)));
+        }
+    }
+    for id in sorted_keys(quests) {
+        let source = format!("rewarded by quest '{}'", id);
+        for item in &quests[id].reward.items {
+            issues.extend(check_usable_on(item, &source, known_room).map(|m| ("quests", m)));
+        }
+    }
+    for id in sorted_keys(npcs) {
+        for (node_id, node) in npcs[id].nodes.iter() {
+            let choice_actions = node.choices.iter().flat_map(|c| c.actions.iter());
+            for action in node.actions.iter().chain(choice_actions) {
+                if let Action::GiveItem { item } = action {
+                    let source = format!("given by NPC '{}' node '{}'", id, node_id);
+                    issues.extend(check_usable_on(item, &source, known_room).map(|m| ("npcs", m)));
+                }
+            }
+        }
+    }
+    for id in sorted_keys(recipes) {
+        let source = format!("made by recipe '{}'", id);
+        issues.extend(check_usable_on(&recipes[id].output, &source, known_room).map(|m| ("recipes", m)));
+    }
+
+    issues.sort();
+    issues
+}
+
+// Sorted so reports are stable between runs
+fn sorted_keys<T>(map: &HashMap<String, T>) -> Vec<&String> {
+    let mut keys: Vec<&String> = map.keys().collect();
+    keys.sort();
+    keys
+}
+
+/// Structural checks for a single world
+pub fn validate_world(
+    world: &World,
+    start_room: &str,
+    enemies: &HashMap<String, Enemy>,
+    quests: &HashMap<String, Quest>,
+    npcs: &HashMap<String, Npc>,
+    recipes: &HashMap<String, Recipe>,
+) -> Vec<(Severity, String)> {
+    let mut issues = Vec::new();
+
+    // Sorted so reports are stable between runs
+    let mut room_ids: Vec<&String> = world.rooms.keys().collect();
+    room_ids.sort();
+
+    if !world.rooms.contains_key(start_room) {
+        issues.push((Severity::Error, format!("start room '{}' does not exist", start_room)));
+    }
+
+    let mut coordinates: HashMap<(i32, i32), &str> = HashMap::new();
+
+    for id in &room_ids {
+        let room = &world.rooms[*id];
+
+        if room.id != **id {
+            issues.push((Severity::Error, format!("room '{}' declares a different id '{}'", id, room.id)));
+        }
+
+        let mut exits: Vec<(&String, &String)> = room.exits.iter().collect();
+        exits.sort();
+        for (direction, target) in exits {
+            match world.rooms.get(target) {
+                None => issues.push((
+                    Severity::Error,
+                    format!("exit '{}' of room '{}' leads to missing room '{}'", direction, id, target),
+                )),
+                Some(target_room) => {
+                    if !target_room.exits.values().any(|back| back == *id) {
+                        issues.push((
+                            Severity::Warning,
+                            format!("exit '{}' from '{}' to '{}' is one-way", direction, id, target),
+                        ));
+                    }
+                }
+            }
+        }
+
+        let mut locks: Vec<(&String, &ExitLock)> = room.locks.iter().collect();
+        locks.sort_by(|a, b| a.0.cmp(b.0));
+        for (direction, lock) in locks {
+            if !room.exits.contains_key(direction) {
+                issues.push((Severity::Error, format!("room '{}' locks missing exit '{}'", id, direction)));
+            }
+            if let Some(cleared) = &lock.cleared_room {
+                if !world.rooms.contains_key(cleared) {
+                    issues.push((
+                        Severity::Error,
+                        format!("lock on '{}' of room '{}' waits on missing room '{}'", direction, id, cleared),
+                    ));
+                }
+            }
+        }
+
+        for container in &room.containers {
+            let Some(cleared) = container.lock.as_ref().and_then(|lock| lock.cleared_room.as_ref()) else {
+                continue;
+            };
+            if !world.rooms.contains_key(cleared) {
+                issues.push((
+                    Severity::Error,
+                    format!("lock on '{}' in room '{}' waits on missing room '{}'", container.name, id, cleared),
+                ));
+            }
+        }
+
+        for action in room.triggers.iter().flat_map(|t| t.actions.iter()) {
+            let mut rooms = Vec::new();
+            match action {
+                Action::SpawnEnemy { enemy, room: target } => {
+                    if !enemies.contains_key(enemy) {
+                        issues.push((
+                            Severity::Error,
+                            format!("trigger in room '{}' spawns unknown enemy '{}'", id, enemy),
+                        ));
+                    }
+                    rooms.extend(target);
+                }
+                Action::OpenExit { direction, to, room: target } => {
+                    let exit_room = world.rooms.get(target.as_ref().unwrap_or(id));
+                    if to.is_none() && exit_room.is_some_and(|r| !r.exits.contains_key(direction)) {
+                        issues.push((
+                            Severity::Error,
+                            format!("trigger in room '{}' opens missing exit '{}'", id, direction),
+                        ));
+                    }
+                    rooms.extend(to);
+                    rooms.extend(target);
+                }
+                Action::Teleport { room: target } => rooms.push(target),
+                Action::StartQuest { quest } if !quests.contains_key(quest) => issues.push((
+                    Severity::Error,
+                    format!("trigger in room '{}' starts unknown quest '{}'", id, quest),
+                )),
+                Action::LearnRecipe { recipe } if !recipes.contains_key(recipe) => issues.push((
+                    Severity::Error,
+                    format!("trigger in room '{}' teaches unknown recipe '{}'", id, recipe),
+                )),
+                _ => {}
+            }
+
+            for target in rooms.into_iter().filter(|r| !world.rooms.contains_key(*r)) {
+                issues.push((
+                    Severity::Error,
+                    format!("trigger in room '{}' refers to missing room '{}'", id, target),
+                ));
+            }
+        }
+
+        if let Some(enemy) = &room.enemy {
+            if !enemies.contains_key(enemy) {
+                issues.push((Severity::Error, format!("room '{}' has unknown enemy '{}'", id, enemy)));
+            }
+        }
+
+        for npc in room.npcs.iter().filter(|n| !npcs.contains_key(*n)) {
+            issues.push((Severity::Error, format!("room '{}' has unknown NPC '{}'", id, npc)));
+        }
+
+        let free_wares = room.shop.iter().flat_map(|shop| shop.stock.iter()).filter(|e| e.price() == 0);
+        for entry in free_wares {
+            issues.push((Severity::Warning, format!("shop in room '{}' gives away '{}' for free", id, entry.item.name)));
+        }
+
+        // Everything the room can hand out: on the floor, in containers, for sale or from triggers
+        let contained = room.containers.iter().flat_map(|c| c.items.iter());
+        let for_sale = room.shop.iter().flat_map(|shop| shop.stock.iter().map(|e| &e.item));
+        let given = room.triggers.iter().flat_map(|t| t.actions.iter()).filter_map(|action| match action {
+            Action::GiveItem { item } => Some(item),
+            _ => None,
+        });
+        let source = format!("in '{}'", id);
+        for item in room.items.iter().chain(contained).chain(for_sale).chain(given) {
+            let issue = check_usable_on(item, &source, |target| world.rooms.contains_key(target));
+            issues.extend(issue.map(|message| (Severity::Error, message)));
+        }
+
+        if let Some(other) = coordinates.insert((room.x, room.y), id) {
+            issues.push((
+                Severity::Warning,
+                format!("rooms '{}' and '{}' share coordinates ({}, {})", other, id, room.x, room.y),
+            ));
+        }
+    }
+
+    let reachable = reachable_rooms(world, start_room);
+    for id in &room_ids {
+        if world.rooms.contains_key(start_room) && !reachable.contains(id.as_str()) {
+            issues.push((Severity::Warning, format!("room '{}' is unreachable from '{}'", id, start_room)));
+        }
+    }
+
+    issues
+}
+
+// Dialogue must lead somewhere real. Rooms aren't checked since an NPC isn't tied to one level.
+fn validate_npc(
+    id: &str,
+    npc: &Npc,
+    enemies: &HashMap<String, Enemy>,
+    quests: &HashMap<String, Quest>,
+    recipes: &HashMap<String, Recipe>,
+) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    if !npc.nodes.contains_key(&npc.start) {
+        issues.push(format!("NPC '{}' starts at missing node '{}'", id, npc.start));
+    }
+
+    let mut node_ids: Vec<&String> = npc.nodes.keys().collect();
+    node_ids.sort();
+    for node_id in node_ids {
+        let node = &npc.nodes[node_id];
+        for next in node.choices.iter().filter_map(|c| c.next.as_ref()) {
+            if !npc.nodes.contains_key(next) {
+                issues.push(format!("NPC '{}' node '{}' leads to missing node '{}'", id, node_id, next));
+            }
+        }
+
+        let choice_actions = node.choices.iter().flat_map(|c| c.actions.iter());
+        for action in node.actions.iter().chain(choice_actions) {
+            match action {
+                Action::SpawnEnemy { enemy, .. } if !enemies.contains_key(enemy) => {
+                    issues.push(format!("NPC '{}' node '{}' spawns unknown enemy '{}'", id, node_id, enemy));
+                }
+                Action::StartQuest { quest } if !quests.contains_key(quest) => {
+                    issues.push(format!("NPC '{}' node '{}' starts unknown quest '{}'", id, node_id, quest));
+                }
+                Action::LearnRecipe { recipe } if !recipes.contains_key(recipe) => {
+                    issues.push(format!("NPC '{}' node '{}' teaches unknown recipe '{}'", id, node_id, recipe));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    issues
+}
+
+// Inputs and tools are matched by name at runtime, so a typo makes a recipe impossible
+fn validate_recipes(
+    items: &HashMap<String, Item>,
+    recipes: &HashMap<String, Recipe>,
+    rooms: &HashSet<String>,
+) -> Vec<String> {
+    let mut issues = Vec::new();
+    let known_item = |name: &str| items.values().any(|item| item.name.eq_ignore_ascii_case(name));
+
+    let mut recipe_ids: Vec<&String> = recipes.keys().collect();
+    recipe_ids.sort();
+    for id in recipe_ids {
+        let recipe = &recipes[id];
+        for input in recipe.inputs.iter().filter(|i| !known_item(&i.item)) {
+            issues.push(format!("recipe '{}' needs unknown item '{}'", id, input.item));
+        }
+        if let Some(tool) = recipe.tool.as_ref().filter(|t| !known_item(t)) {
+            issues.push(format!("recipe '{}' needs unknown tool '{}'", id, tool));
+        }
+        if let Some(room) = recipe.room.as_ref().filter(|r| !rooms.contains(*r)) {
+            issues.push(format!("recipe '{}' is made in missing room '{}'", id, room));
+        }
+    }
+
+    let mut item_ids: Vec<&String> = items.keys().collect();
+    item_ids.sort();
+    for id in item_ids {
+        if let Some(recipe) = items[id].teaches.as_ref().filter(|r| !recipes.contains_key(*r)) {
+            issues.push(format!("item '{}' teaches unknown recipe '{}'", id, recipe));
+        }
+    }
+
+    issues
+}
+
+fn reachable_rooms<'a>(world: &'a World, start_room: &'a str) -> HashSet<&'a str> {
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([start_room]);
+
+    while let Some(id) = queue.pop_front() {
+        if !visited.insert(id) {
+            continue;
+        }
+        if let Some(room) = world.rooms.get(id) {
+            queue.extend(room.exits.values().map(String::as_str));
+        }
+    }
+
+    visited
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::fs;
+    use crate::campaign::load_campaign;
+
+    fn amulet(usable_on: &str) -> Value {
+        json!({ "name": "Amulet", "item_type": "Quest", "power": null, "usable_on": usable_on })
+    }
+
+    fn check(world: Value) -> Vec<(Severity, String)> {
+        let world: World = serde_json::from_value(world).unwrap();
+        let none = HashMap::new();
+        validate_world(&world, "hall", &none, &HashMap::new(), &HashMap::new(), &HashMap::new())
+    }
+
+    fn has(issues: &[(Severity, String)], severity: Severity, message: &str) -> bool {
+        issues.iter().any(|(s, m)| *s == severity && m == message)
+    }
+
+    #[test]
+    fn reports_dangling_and_one_way_exits() {
+        let issues = check(json!({ "rooms": {
+            "hall": { "id": "hall", "description": "", "exits": { "north": "vault", "east": "yard" } },
+            "yard": { "id": "yard", "description": "", "x": 1 }
+        }}));
+
+        assert!(has(&issues, Severity::Error, "exit 'north' of room 'hall' leads to missing room 'vault'"));
+        assert!(has(&issues, Severity::Warning, "exit 'east' from 'hall' to 'yard' is one-way"));
+    }
+
+    #[test]
+    fn reports_quest_items_for_missing_rooms_wherever_they_are() {
+        let issues = check(json!({ "rooms": {
+            "hall": {
+                "id": "hall",
+                "description": "",
+                "items": [amulet("altar")],
+                "containers": [{ "name": "chest", "items": [amulet("crypt")] }],
+                "shop": { "name": "Stall", "stock": [{ "item": amulet("tower"), "price": 5 }] },
+                "triggers": [{ "on": "on_enter", "actions": [{ "type": "give_item", "item": amulet("hall") }] }]
+            }
+        }}));
+
+        for target in ["altar", "crypt", "tower"] {
+            let message = format!("quest item 'Amulet' in 'hall' is usable on missing room '{}'", target);
+            assert!(has(&issues, Severity::Error, &message), "{}", target);
+        }
+        assert_eq!(issues.len(), 3);
+    }
+
+    #[test]
+    fn reports_unknown_item_ids_and_items_from_outside_levels() {
+        let dir = std::env::temp_dir().join(format!("shards_of_aether_validate_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("campaign.json"),
+            json!({ "start": "one", "levels": [{ "id": "one", "file": "one.json", "title": "One", "start_room": "hall" }] })
+                .to_string(),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("one.json"),
+            json!({ "rooms": { "hall": { "id": "hall", "description": "", "items": ["no_such_item"] } } }).to_string(),
+        )
+        .unwrap();
+        let campaign = load_campaign(dir.join("campaign.json")).unwrap();
+
+        let items: HashMap<String, Item> =
+            HashMap::from([("amulet".to_string(), serde_json::from_value(amulet("altar")).unwrap())]);
+        let enemies: HashMap<String, Enemy> = serde_json::from_value(json!({
+            "Goblin": {
+                "name": "Goblin", "description": "", "health": 1, "attack": 1, "xp_reward": 1,
+                "loot": [{ "weight": 1, "item": amulet("crypt") }]
+            }
+        }))
+        .unwrap();
+        let quests: HashMap<String, Quest> = serde_json::from_value(json!({
+            "errand": { "title": "Errand", "objectives": [], "reward": { "items": [amulet("hall")] } }
+        }))
+        .unwrap();
+
+        let issues = validate_campaign(&campaign, &items, &enemies, &quests, &HashMap::new(), &HashMap::new());
+        fs::remove_dir_all(&dir).unwrap();
+
+        let found = |level: &str, text: &str| {
+            issues.iter().any(|i| i.severity == Severity::Error && i.level == level && i.message.contains(text))
+        };
+        assert!(found("one", "unknown item id 'no_such_item'"));
+        assert!(found("items", "quest item 'Amulet' 'amulet' in the registry is usable on missing room 'altar'"));
+        assert!(found("enemies", "quest item 'Amulet' dropped by 'Goblin' is usable on missing room 'crypt'"));
+        // The broken level never loaded, so its rooms are unknown too
+        assert!(found("quests", "rewarded by quest 'errand' is usable on missing room 'hall'"));
+    }
+
+    #[test]
+    fn strict_fails_on_warnings() {
+        let warning = Issue { severity: Severity::Warning, level: "one".to_string(), message: String::new() };
+        let error = Issue { severity: Severity::Error, ..warning.clone() };
+
+        assert!(!fails(&[], true));
+        assert!(!fails(std::slice::from_ref(&warning), false));
+        assert!(fails(std::slice::from_ref(&warning), true));
+        assert!(fails(&[warning, error], false));
+    }
+}
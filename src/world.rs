@@ -1,9 +1,15 @@
     use serde::{Serialize, Deserialize};
-    use crate::player::Player;
-    use crate::items::{Item, ItemType};
+    use crate::player::{InventoryLimits, Player};
+    use crate::items::{self, Item, ItemType};
     use crate::enemies::get_enemy_by_name; 
-    use crate::combat::start_combat;
-    use crate::colors::{colored_text, MessageType};
+    use crate::crafting;
+    use crate::npcs;
+    use crate::shops::Shop;
+    use crate::combat::CombatState;
+    use crate::triggers::{self, Trigger, TriggerEvent};
+    use crate::quests::{self, ObjectiveKind};
+    use crate::events::{GameEvent, RoomView};
+    use crate::error::{GameError, GameResult};
     use std::collections::{HashMap, HashSet, VecDeque};
     use itertools::Itertools;
 
@@ -11,219 +17,592 @@
     pub struct Room {
         pub id: String,
         pub description: String,
-        #[serde(default)]
+        #[serde(default, deserialize_with = "items::deserialize_items")]
         pub items: Vec<Item>,
         #[serde(default)]
         pub exits: HashMap<String, String>,
+        /// Requirements for exits that start closed, keyed by direction
+        #[serde(default)]
+        pub locks: HashMap<String, ExitLock>,
         #[serde(default)]
         pub enemy: Option<String>,
+        /// Scenery that can be examined, keyed by name
+        #[serde(default)]
+        pub features: HashMap<String, String>,
+        #[serde(default)]
+        pub containers: Vec<Container>,
+        #[serde(default)]
+        pub triggers: Vec<Trigger>,
+        /// Ids of the NPCs standing here
+        #[serde(default)]
+        pub npcs: Vec<String>,
+        #[serde(default)]
+        pub shop: Option<Shop>,
         #[serde(default)]
         pub x: i32,
         #[serde(default)]
         pub y: i32,
     }
 
+    /// Keeps an exit shut until every listed requirement is met.
+    /// Once open, the lock is removed from the room for good.
+    #[derive(Serialize, Deserialize, Clone, Debug, Default)]
+    pub struct ExitLock {
+        /// Item that opens the exit with `use <key>`
+        #[serde(default)]
+        pub key: Option<String>,
+        /// Player flag that must be set
+        #[serde(default)]
+        pub flag: Option<String>,
+        /// Room whose enemy must be defeated
+        #[serde(default)]
+        pub cleared_room: Option<String>,
+        /// Shown when the player tries the locked exit
+        #[serde(default)]
+        pub message: Option<String>,
+    }
+
+    impl ExitLock {
+        // Keys never open on their own; they have to be used
+        fn is_satisfied(&self, player: &Player, world: &World) -> bool {
+            self.key.is_none()
+                && self.flag.as_ref().is_none_or(|flag| player.flags.contains(flag))
+                && self.cleared_room.as_ref().is_none_or(|id| {
+                    world.rooms.get(id).is_some_and(|room| room.enemy.is_none())
+                })
+        }
+    }
+
+    /// A chest, bag or box holding items of its own
+    #[derive(Serialize, Deserialize, Clone, Debug)]
+    pub struct Container {
+        pub name: String,
+        #[serde(default)]
+        pub description: String,
+        #[serde(default, deserialize_with = "items::deserialize_items")]
+        pub items: Vec<Item>,
+        /// Requirements for opening it, with the same rules as an exit lock
+        #[serde(default)]
+        pub lock: Option<ExitLock>,
+        /// Hidden containers only show up after a `search`
+        #[serde(default)]
+        pub hidden: bool,
+        #[serde(default)]
+        pub open: bool,
+    }
+
+    impl Room {
+        // Containers the player knows about, looked up by name
+        fn container_index(&self, name: &str) -> Option<usize> {
+            self.containers
+                .iter()
+                .position(|c| !c.hidden && c.name.eq_ignore_ascii_case(name))
+        }
+    }
+
     #[derive(Serialize, Deserialize, Clone)]
     pub struct World {
         pub rooms: HashMap<String, Room>,
         #[serde(default)]
         pub cleared_rooms: HashSet<String>,
+        /// Rooms the player has entered at least once
+        #[serde(default)]
+        pub visited: HashSet<String>,
     }
 
     // === MOVEMENT ===
 
-    pub fn move_player(direction: String, player: &mut Player, world: &mut World) {
-        if let Some(room) = world.rooms.get(&player.current_room) {
-            if let Some(next_room_id) = room.exits.get(&direction) {
-                let previous_room = player.current_room.clone();
-                player.current_room = next_room_id.clone();
-
-                println!("You move {}.", direction);
-                println!("🧍 You have entered: {}", player.current_room);
-                look(player, world);
-
-                // Trigger combat if enemy exists
-                if let Some(next_room) = world.rooms.get(&player.current_room) {
-                    if let Some(enemy_name) = &next_room.enemy {
-                        if let Some(mut enemy) = get_enemy_by_name(enemy_name) {
-                            println!("\n⚔️ A wild {} appears!", enemy.name);
-                            let ran_away = start_combat(player, &mut enemy, &previous_room);
-
-                            // Remove enemy if defeated
-                            if enemy.health <= 0 {
-                                println!("{} has been defeated!", enemy.name);
-                                if let Some(room_mut) = world.rooms.get_mut(&player.current_room) {
-                                    room_mut.enemy = None;
-                                    world.cleared_rooms.insert(player.current_room.clone());
-                                }
-                            }
-
-                            // Retreat logic
-                            if ran_away {
-                                player.current_room = previous_room;
-                                println!("You have escaped back to {}.", player.current_room);
-                            }
-                        } else {
-                            println!("(⚠️ Warning: Enemy '{}' not found!)", enemy_name);
-                        }
-                    }
-                }
-            } else {
-                println!("You can't go that way.");
+    /// Moves the player and returns the fight waiting in the new room, if any
+    pub fn move_player(
+        direction: String,
+        player: &mut Player,
+        world: &mut World,
+        events: &mut Vec<GameEvent>,
+    ) -> GameResult<Option<CombatState>> {
+        let room = world
+            .rooms
+            .get(&player.current_room)
+            .ok_or_else(|| GameError::MissingRoom(player.current_room.clone()))?;
+
+        let Some(next_room_id) = room.exits.get(&direction) else {
+            events.push(GameEvent::NoExit);
+            return Ok(None);
+        };
+        if !world.rooms.contains_key(next_room_id) {
+            return Err(GameError::MissingRoom(next_room_id.clone()));
+        }
+        let next_room_id = next_room_id.clone();
+
+        if let Some(lock) = room.locks.get(&direction) {
+            if !lock.is_satisfied(player, world) {
+                events.push(GameEvent::ExitLocked {
+                    direction,
+                    message: lock.message.clone(),
+                });
+                return Ok(None);
+            }
+            if let Some(room) = world.rooms.get_mut(&player.current_room) {
+                room.locks.remove(&direction);
+            }
+        }
+
+        let previous_room = player.current_room.clone();
+        player.current_room = next_room_id;
+
+        events.push(GameEvent::Moved {
+            direction,
+            room: player.current_room.clone(),
+        });
+        enter_room(player, world, events);
+
+        // Trigger combat if enemy exists (a trigger may have moved the player on)
+        let Some(enemy_name) = world.rooms.get(&player.current_room).and_then(|r| r.enemy.as_ref()) else {
+            return Ok(None);
+        };
+        let enemy = get_enemy_by_name(enemy_name)
+            .ok_or_else(|| GameError::MissingEnemy(enemy_name.clone()))?;
+        Ok(Some(CombatState::new(enemy, &player.current_room, &previous_room, events)))
+    }
+
+    /// Everything that happens on arriving in the player's current room, however they got there.
+    /// A fight with the room's enemy is left to the caller.
+    pub fn enter_room(player: &mut Player, world: &mut World, events: &mut Vec<GameEvent>) {
+        look(player, world, events);
+        quests::record(ObjectiveKind::Visit, &player.current_room.clone(), player, events);
+
+        if world.visited.insert(player.current_room.clone()) {
+            triggers::fire(TriggerEvent::OnFirstEnter, None, player, world, events);
+        }
+        triggers::fire(TriggerEvent::OnEnter, None, player, world, events);
+    }
+
+    /// Gives rooms saved before they had triggers the ones their level defines now
+    pub fn restore_triggers(world: &mut World, level: World) {
+        for (id, room) in level.rooms {
+            if let Some(saved) = world.rooms.get_mut(&id).filter(|saved| saved.triggers.is_empty()) {
+                saved.triggers = room.triggers;
             }
         }
     }
 
+    /// Removes a defeated enemy from its room
+    pub fn clear_enemy(room_id: &str, world: &mut World) {
+        if let Some(room) = world.rooms.get_mut(room_id) {
+            room.enemy = None;
+            world.cleared_rooms.insert(room_id.to_string());
+        }
+    }
+
+    /// Leaves an item on the floor of a room
+    pub fn place_item(room_id: &str, item: Item, world: &mut World) {
+        if let Some(room) = world.rooms.get_mut(room_id) {
+            items::add_to_stack(&mut room.items, item);
+        }
+    }
+
     // === ROOM DESCRIPTION ===
     
-    pub fn look(player: &Player, world: &World) {
-        if let Some(room) = world.rooms.get(&player.current_room) {
-            println!("{}", colored_text(&room.id, MessageType::Action));
-            println!("\n{}", room.description);
-
-            if !room.items.is_empty() {
-                println!("You see:");
-                for item in &room.items {
-                    println!(" - {}", colored_text(&item.name, MessageType::Item));
-                }
-            }
+    pub fn look(player: &Player, world: &World, events: &mut Vec<GameEvent>) {
+        let Some(room) = world.rooms.get(&player.current_room) else {
+            events.push(GameEvent::Error(GameError::MissingRoom(player.current_room.clone()).to_string()));
+            return;
+        };
+
+        events.push(GameEvent::RoomDescribed(RoomView {
+            id: room.id.clone(),
+            description: room.description.clone(),
+            items: room.items.iter().map(|i| (i.name.clone(), i.quantity)).collect(),
+            exits: room.exits.keys().cloned().collect(),
+            locked_exits: room.locks.keys().cloned().collect(),
+            containers: room
+                .containers
+                .iter()
+                .filter(|c| !c.hidden)
+                .map(|c| (c.name.clone(), c.open))
+                .collect(),
+            enemy: room.enemy.clone(),
+            npcs: room
+                .npcs
+                .iter()
+                .filter_map(|id| npcs::get_npc(id))
+                .map(|npc| npc.name)
+                .collect(),
+            shop: room.shop.as_ref().map(|shop| shop.name.clone()),
+        }));
+    }
+
+    // === EXAMINING ===
+
+    /// Describes an item carried or in the room, the room's enemy, or a room feature
+    pub fn examine(target: &str, player: &Player, world: &World, events: &mut Vec<GameEvent>) {
+        if target.is_empty() {
+            look(player, world, events);
+            return;
+        }
+
+        let room = world.rooms.get(&player.current_room);
+        let carried = player
+            .inventory
+            .iter()
+            .chain(player.equipment.find(target).and_then(|slot| player.equipment.get(slot)));
+        let on_floor = room.into_iter().flat_map(|r| r.items.iter());
+
+        if let Some(item) = carried.chain(on_floor).find(|i| i.name.eq_ignore_ascii_case(target)) {
+            events.push(GameEvent::ItemExamined(item.clone()));
+            return;
+        }
+
+        let Some(room) = room else {
+            events.push(GameEvent::NothingToExamine(target.to_string()));
+            return;
+        };
+
+        if let Some(enemy) = room
+            .enemy
+            .as_ref()
+            .filter(|name| name.eq_ignore_ascii_case(target))
+            .and_then(|name| get_enemy_by_name(name))
+        {
+            events.push(GameEvent::EnemyExamined {
+                name: enemy.name,
+                description: enemy.description,
+                health: enemy.health,
+                attack: enemy.attack,
+            });
+            return;
+        }
+
+        if let Some((_, npc)) = npcs::find_here(target, player, world) {
+            events.push(GameEvent::FeatureExamined { name: npc.name, description: npc.description });
+            return;
+        }
 
-            if !room.exits.is_empty() {
-                let exits = room.exits.keys()
-                    .map(|e| colored_text(e, MessageType::Action).to_string())
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                println!("Exits: {}", exits);
+        if let Some(container) = room.container_index(target).map(|i| &room.containers[i]) {
+            events.push(GameEvent::FeatureExamined {
+                name: container.name.clone(),
+                description: container.description.clone(),
+            });
+            return;
+        }
+
+        match room.features.iter().find(|(name, _)| name.eq_ignore_ascii_case(target)) {
+            Some((name, description)) => events.push(GameEvent::FeatureExamined {
+                name: name.clone(),
+                description: description.clone(),
+            }),
+            None => events.push(GameEvent::NothingToExamine(target.to_string())),
+        }
+    }
+
+    // === CONTAINERS ===
+
+    /// Opens a container, using up its key if it needs one, and lists what's inside
+    pub fn open_container(name: &str, player: &mut Player, world: &mut World, events: &mut Vec<GameEvent>) {
+        let Some(room) = world.rooms.get(&player.current_room) else { return };
+        let Some(index) = room.container_index(name) else {
+            events.push(GameEvent::NoSuchContainer(name.to_string()));
+            return;
+        };
+        let container = &room.containers[index];
+
+        if let Some(lock) = &container.lock {
+            // Check everything before spending the key
+            let has_key = lock
+                .key
+                .as_ref()
+                .is_none_or(|key| items::find_item(&player.inventory, key).is_some());
+            let rest = ExitLock { key: None, ..lock.clone() };
+            if !has_key || !rest.is_satisfied(player, world) {
+                events.push(GameEvent::ContainerLocked {
+                    container: container.name.clone(),
+                    message: lock.message.clone(),
+                });
+                return;
             }
 
-            if let Some(enemy_name) = &room.enemy {
-                println!("⚠️ {}", colored_text(&format!("You sense danger nearby... ({})", enemy_name), MessageType::Enemy));
+            if let Some(pos) = lock.key.as_ref().and_then(|key| items::find_item(&player.inventory, key)) {
+                let key = items::take_from_stack(&mut player.inventory, pos, 1);
+                events.push(GameEvent::ContainerUnlocked { container: container.name.clone(), item: key.name });
             }
         }
+
+        let Some(container) = world
+            .rooms
+            .get_mut(&player.current_room)
+            .map(|room| &mut room.containers[index])
+        else {
+            return;
+        };
+        container.lock = None;
+        container.open = true;
+        events.push(GameEvent::ContainerOpened {
+            container: container.name.clone(),
+            items: container.items.iter().map(|i| (i.name.clone(), i.quantity)).collect(),
+        });
+    }
+
+    /// Searching the room turns up hidden containers; searching a container opens it
+    pub fn search(target: &str, player: &mut Player, world: &mut World, events: &mut Vec<GameEvent>) {
+        if !target.is_empty() {
+            open_container(target, player, world, events);
+            return;
+        }
+
+        let Some(room) = world.rooms.get_mut(&player.current_room) else { return };
+        let mut found = Vec::new();
+        for container in room.containers.iter_mut().filter(|c| c.hidden) {
+            container.hidden = false;
+            found.push(container.name.clone());
+        }
+        events.push(GameEvent::Searched { found });
+    }
+
+    /// Takes `count` of an item out of an open container, or the whole stack when no count is given
+    pub fn take_from_container(
+        item_name: &str,
+        count: Option<u32>,
+        container: &str,
+        limits: &InventoryLimits,
+        player: &mut Player,
+        world: &mut World,
+        events: &mut Vec<GameEvent>,
+    ) {
+        let Some(container) = open_container_mut(container, player, world, events) else { return };
+        pick_up(item_name, count, &mut container.items, limits, player, events);
+    }
+
+    /// Stores `count` of an item in an open container, or the whole stack when no count is given
+    pub fn put_item(
+        item_name: &str,
+        count: Option<u32>,
+        container: &str,
+        player: &mut Player,
+        world: &mut World,
+        events: &mut Vec<GameEvent>,
+    ) {
+        let Some(pos) = items::find_item(&player.inventory, item_name) else {
+            events.push(GameEvent::NotInInventory(item_name.to_string()));
+            return;
+        };
+        let Some(container) = open_container_mut(container, player, world, events) else { return };
+
+        let count = count.unwrap_or(player.inventory[pos].quantity);
+        let item = items::take_from_stack(&mut player.inventory, pos, count);
+        events.push(GameEvent::ItemStored {
+            item: item.name.clone(),
+            count: item.quantity,
+            container: container.name.clone(),
+        });
+        items::add_to_stack(&mut container.items, item);
+    }
+
+    // A visible container in the player's room that has already been opened
+    fn open_container_mut<'a>(
+        name: &str,
+        player: &Player,
+        world: &'a mut World,
+        events: &mut Vec<GameEvent>,
+    ) -> Option<&'a mut Container> {
+        let room = world.rooms.get_mut(&player.current_room)?;
+        let Some(index) = room.container_index(name) else {
+            events.push(GameEvent::NoSuchContainer(name.to_string()));
+            return None;
+        };
+
+        let container = &mut room.containers[index];
+        if !container.open {
+            events.push(GameEvent::ContainerClosed(container.name.clone()));
+            return None;
+        }
+        Some(container)
     }
 
     // === ITEM HANDLING 
 
-    pub fn take_item(item_name: &str, player: &mut Player, world: &mut World) {
-        // Block picking up items in the Sanctum
-        if player.current_room.eq_ignore_ascii_case("sanctum") {
-            println!("You can't take items here. The Shards and relics are protected.");
+    /// Picks up `count` of an item, or the whole stack when no count is given
+    pub fn take_item(
+        item_name: &str,
+        count: Option<u32>,
+        limits: &InventoryLimits,
+        player: &mut Player,
+        world: &mut World,
+        events: &mut Vec<GameEvent>,
+    ) {
+        let Some(name) = world.rooms.get(&player.current_room).and_then(|room| {
+            items::find_item(&room.items, item_name).map(|pos| room.items[pos].name.clone())
+        }) else {
+            events.push(GameEvent::ItemNotHere(item_name.to_string()));
+            return;
+        };
+        if triggers::fire(TriggerEvent::OnTake, Some(&name), player, world, events) {
             return;
         }
 
-        if let Some(room) = world.rooms.get_mut(&player.current_room) {
-            if let Some(pos) = room.items.iter().position(|i| i.name.eq_ignore_ascii_case(item_name)) {
-                let item = room.items.remove(pos);
-                println!("You picked up: {}", colored_text(&item.name, MessageType::Item));
-                player.inventory.push(item.clone());
+        let Some(room) = world.rooms.get_mut(&player.current_room) else { return };
+        if pick_up(item_name, count, &mut room.items, limits, player, events) > 0 {
+            world.cleared_rooms.insert(player.current_room.clone());
+        }
+    }
 
-                world.cleared_rooms.insert(player.current_room.clone());
-            } else {
-                println!("There is no {} here.", item_name);
+    // Moves items from a room or container into the inventory and returns how many were taken
+    fn pick_up(
+        item_name: &str,
+        count: Option<u32>,
+        source: &mut Vec<Item>,
+        limits: &InventoryLimits,
+        player: &mut Player,
+        events: &mut Vec<GameEvent>,
+    ) -> u32 {
+        let Some(first) = items::find_item(source, item_name) else {
+            events.push(GameEvent::ItemNotHere(item_name.to_string()));
+            return 0;
+        };
+
+        let name = source[first].name.clone();
+        let wanted = count.unwrap_or(source[first].quantity);
+        let mut taken = 0;
+
+        // One unit at a time, so limits stop the pickup exactly where they're reached
+        while taken < wanted {
+            let Some(pos) = items::find_item(source, &name) else { break };
+            if !player.can_carry(&source[pos], limits) {
+                events.push(GameEvent::InventoryFull(name.clone()));
+                break;
             }
+            player.add_item(items::take_from_stack(source, pos, 1));
+            taken += 1;
         }
+
+        if taken > 0 {
+            events.push(GameEvent::ItemTaken { item: name.clone(), count: taken });
+            quests::record(ObjectiveKind::Take, &name, player, events);
+        }
+        taken
+    }
+
+    /// Leaves `count` of an item in the current room, or the whole stack when no count is given
+    pub fn drop_item(
+        item_name: &str,
+        count: Option<u32>,
+        player: &mut Player,
+        world: &mut World,
+        events: &mut Vec<GameEvent>,
+    ) {
+        let Some(pos) = items::find_item(&player.inventory, item_name) else {
+            events.push(GameEvent::NotInInventory(item_name.to_string()));
+            return;
+        };
+
+        let count = count.unwrap_or(player.inventory[pos].quantity);
+        let item = items::take_from_stack(&mut player.inventory, pos, count);
+        events.push(GameEvent::ItemDropped { item: item.name.clone(), count: item.quantity });
+        place_item(&player.current_room, item, world);
     }
 
-    pub fn use_item(item_name: &str, player: &mut Player, world: &mut World) -> bool {
+    pub fn use_item(item_name: &str, player: &mut Player, world: &mut World, events: &mut Vec<GameEvent>) -> bool {
         let mut level_completed = false;
 
-        if let Some(pos) = player
-            .inventory
-            .iter()
-            .position(|i| i.name.eq_ignore_ascii_case(item_name))
-        {
+        if let Some(pos) = items::find_item(&player.inventory, item_name) {
+            // Room triggers get the first say, and may stop the item's usual effect
+            let name = player.inventory[pos].name.clone();
+            if triggers::fire(TriggerEvent::OnUseItem, Some(&name), player, world, events) {
+                return false;
+            }
+            let Some(pos) = items::find_item(&player.inventory, &name) else { return false };
             let item = &player.inventory[pos];
+            let mut used = true;
+
+            // Keys open locked exits in the current room before anything else
+            if let Some(room) = world.rooms.get_mut(&player.current_room) {
+                let unlocked = room
+                    .locks
+                    .iter()
+                    .find(|(_, lock)| lock.key.as_ref().is_some_and(|key| key.eq_ignore_ascii_case(&item.name)))
+                    .map(|(direction, _)| direction.clone());
+
+                if let Some(direction) = unlocked {
+                    let lock = room.locks.remove(&direction).unwrap_or_default();
+                    let item = items::take_from_stack(&mut player.inventory, pos, 1);
+                    events.push(GameEvent::ExitUnlocked { item: item.name, direction: direction.clone() });
+
+                    // Any other requirements still apply, without the key
+                    let rest = ExitLock { key: None, ..lock };
+                    if rest.flag.is_some() || rest.cleared_room.is_some() {
+                        room.locks.insert(direction, rest);
+                    }
+                    quests::record(ObjectiveKind::Use, &name, player, events);
+                    return false;
+                }
+            }
 
             match item.item_type {
                 ItemType::Healing => {
+                    let item = items::take_from_stack(&mut player.inventory, pos, 1);
                     if let Some(amount) = item.power {
                         player.health += amount;
-                        println!(
-                            "💖 You use {} and restore {} HP! Current HP: {}",
-                            item.name, amount, player.health
-                        );
-                    } else {
-                        println!("💖 You use {}, but it had no effect.", item.name);
+                        events.push(GameEvent::ItemHealed {
+                            item: item.name.clone(),
+                            amount,
+                            health: player.health,
+                        });
+                    }
+                    if !player.apply_item_effects(&item, events) && item.power.is_none() {
+                        events.push(GameEvent::ItemNoEffect(item.name));
                     }
-                    player.inventory.remove(pos);
                 }
 
-                ItemType::Weapon => {
-                    if let Some(dmg) = item.power {
-                        player.base_attack += dmg;
-                        println!("⚔️ Equipped {}! Attack +{}", item.name, dmg);
-                    }
-                    player.inventory.remove(pos);
+                // Using gear puts it on
+                ItemType::Weapon | ItemType::Armor | ItemType::Trinket => {
+                    let name = item.name.clone();
+                    player.equip(&name, events);
                 }
 
                 ItemType::Quest => {
                     if let Some(target) = &item.usable_on {
                         if player.current_room == *target {
-                            println!(
-                                "✨ You place the {} on the {}. The path forward opens!",
-                                item.name, target
-                            );
+                            events.push(GameEvent::QuestItemPlaced {
+                                item: item.name.clone(),
+                                target: target.clone(),
+                            });
 
                             // Remove quest item and mark room cleared
-                            player.inventory.remove(pos);
+                            items::take_from_stack(&mut player.inventory, pos, 1);
                             world.cleared_rooms.insert(player.current_room.clone());
 
-                            // === LEVEL COMPLETION LOGIC
-
-                            // Tutorial end → Level 1
-                            if player.current_level == 0
-                                && !player.flags.contains(&"tutorial_completed".to_string())
-                            {
-                                player.flags.push("tutorial_completed".to_string());
-                            }
-
-                            // Level 1 end → Level 2
-                            else if player.current_level == 1
-                                && !player.flags.contains(&"level1_completed".to_string())
-                            {
-                                player.flags.push("level1_completed".to_string());
-                                println!("{}", colored_text(
-                                    "🌿 The forest’s magic subsides... A desert wind begins to blow from afar.",
-                                    MessageType::Info
-                                ));
-                            }
-
-                            // Level 2 end → Realm of Aether
-                            else if player.current_level == 2
-                                && !player.flags.contains(&"level2_completed".to_string())
-                            {
-                                player.flags.push("level2_completed".to_string());
-                                println!("{}", colored_text(
-                                    "🌌 The relic hums with light. Reality bends... You are drawn into the Realm of Aether!",
-                                    MessageType::Success
-                                ));
-                                println!("{}", colored_text(
-                                    "🏆 You win! You have returned all the Shards of Aether to their rightful places, forever to be guarded by the ancients.",
-                                    MessageType::Success
-                                ));
-                            }
-
                             level_completed = true;
                         } else {
-                            println!("You can’t use the {} here.", item.name);
+                            events.push(GameEvent::CannotUseHere(item.name.clone()));
+                            used = false;
                         }
                     } else {
-                        println!("That item can’t be used directly.");
+                        events.push(GameEvent::NotUsable);
+                        used = false;
                     }
                 }
 
                 ItemType::Utility => {
-                    if item.name.eq_ignore_ascii_case("map") {
-                        print_map(player, world);
+                    if let Some(recipe) = item.teaches.clone() {
+                        // Scrolls are used up once their recipe is learned
+                        if crafting::learn(&recipe, player, events) {
+                            items::take_from_stack(&mut player.inventory, pos, 1);
+                        } else {
+                            used = false;
+                        }
+                    } else if item.name.eq_ignore_ascii_case("map") {
+                        events.push(GameEvent::Map(render_map(player, world)));
                     } else if item.name.eq_ignore_ascii_case("water flask") {
-                        println!("💧 You use the Water Flask to refresh yourself.");
+                        events.push(GameEvent::Narration("💧 You use the Water Flask to refresh yourself.".to_string()));
                     } else {
-                        println!("You use the {}.", item.name);
+                        events.push(GameEvent::ItemUsed(item.name.clone()));
                     }
                 }
             }
+
+            if used {
+                quests::record(ObjectiveKind::Use, &name, player, events);
+            }
         } else {
-            println!("You don't have a '{}' in your inventory.", item_name);
+            events.push(GameEvent::NotInInventory(item_name.to_string()));
         }
 
         level_completed
@@ -231,8 +610,8 @@
 
     // === MAP RENDERING ===
 
-    pub fn print_map(player: &Player, world: &World) {
-        println!("--- Map ---");
+    /// Lays out the rooms reachable from the player as rows of text
+    pub fn render_map(player: &Player, world: &World) -> Vec<String> {
 
         let mut positions: HashMap<String, (i32, i32)> = HashMap::new();
         let mut visited = HashSet::new();
@@ -277,7 +656,7 @@
             grid[gy][gx] = format!("{:^width$}", format!("{}{}", marker, room_id), width = cell_width);
 
             if let Some(room) = world.rooms.get(room_id) {
-                for (_dir, target) in &room.exits {
+                for target in room.exits.values() {
                     if let Some(&(tx, ty)) = positions.get(target) {
                         let gx_conn = ((*x - min_x) * 2 + (tx - x)) as usize;
                         let gy_conn = ((*y - min_y) * 2 + (ty - y)) as usize;
@@ -290,25 +669,332 @@
             }
         }
 
-        for row in grid {
-            println!("{}", row.join(""));
-        }
-        println!("-----------");
+        grid.into_iter().map(|row| row.join("")).collect()
     }
 
-    // === LEVEL TRANSITION BANNER ===
-    
-    pub fn print_transition_banner(title: &str) {
-        let padding = 6;
-        let inner_width = title.len() + padding * 2;
-        let border_top = format!("╔{}╗", "═".repeat(inner_width));
-        let border_bottom = format!("╚{}╝", "═".repeat(inner_width));
-
-        let line = format!("{:^width$}", format!("☽✧  {}  ✧☾", title), width = inner_width + 2);
-
-        println!();
-        println!("{}", colored_text(&border_top, MessageType::Info));
-        println!("{}", colored_text(&line, MessageType::Info));
-        println!("{}", colored_text(&border_bottom, MessageType::Info));
-        println!();
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use serde_json::{json, Value};
+
+        fn world(rooms: Value) -> World {
+            serde_json::from_value(json!({ "rooms": rooms })).unwrap()
+        }
+
+        fn key() -> Item {
+            serde_json::from_value(json!({ "name": "Iron Key", "item_type": "Utility", "power": null, "usable_on": null }))
+                .unwrap()
+        }
+
+        fn player_in(room: &str) -> Player {
+            Player { current_room: room.to_string(), ..Player::new() }
+        }
+
+        fn gated(lock: Value) -> World {
+            world(json!({
+                "hall": { "id": "hall", "description": "", "exits": { "north": "vault" }, "locks": { "north": lock } },
+                "vault": { "id": "vault", "description": "", "exits": { "south": "hall" } }
+            }))
+        }
+
+        #[test]
+        fn locked_exits_turn_the_player_back() {
+            let mut world = gated(json!({ "key": "Iron Key", "message": "The door is barred." }));
+            let mut player = player_in("hall");
+            let mut events = Vec::new();
+
+            move_player("north".to_string(), &mut player, &mut world, &mut events).unwrap();
+
+            assert_eq!(player.current_room, "hall");
+            assert!(matches!(
+                &events[..],
+                [GameEvent::ExitLocked { direction, message: Some(message) }]
+                    if direction == "north" && message == "The door is barred."
+            ));
+        }
+
+        #[test]
+        fn flag_locks_open_for_good_once_the_flag_is_set() {
+            let mut world = gated(json!({ "flag": "lever_pulled" }));
+            let mut player = player_in("hall");
+            let mut events = Vec::new();
+
+            move_player("north".to_string(), &mut player, &mut world, &mut events).unwrap();
+            assert_eq!(player.current_room, "hall");
+
+            player.flags.push("lever_pulled".to_string());
+            move_player("north".to_string(), &mut player, &mut world, &mut events).unwrap();
+            assert_eq!(player.current_room, "vault");
+            assert!(world.rooms["hall"].locks.is_empty());
+        }
+
+        #[test]
+        fn cleared_room_locks_wait_for_the_enemy() {
+            let mut world = gated(json!({ "cleared_room": "vault" }));
+            world.rooms.get_mut("vault").unwrap().enemy = Some("Goblin".to_string());
+            let mut player = player_in("hall");
+            let mut events = Vec::new();
+
+            move_player("north".to_string(), &mut player, &mut world, &mut events).unwrap();
+            assert_eq!(player.current_room, "hall");
+
+            clear_enemy("vault", &mut world);
+            move_player("north".to_string(), &mut player, &mut world, &mut events).unwrap();
+            assert_eq!(player.current_room, "vault");
+        }
+
+        #[test]
+        fn using_the_key_unlocks_the_exit_and_spends_the_key() {
+            let mut world = gated(json!({ "key": "Iron Key" }));
+            let mut player = player_in("hall");
+            player.add_item(key());
+            let mut events = Vec::new();
+
+            use_item("iron key", &mut player, &mut world, &mut events);
+
+            assert!(player.inventory.is_empty());
+            assert!(world.rooms["hall"].locks.is_empty());
+            assert!(events.iter().any(|e| matches!(e, GameEvent::ExitUnlocked { direction, .. } if direction == "north")));
+
+            // The unlocked exit survives a save
+            let mut world: World = serde_json::from_str(&serde_json::to_string(&world).unwrap()).unwrap();
+            move_player("north".to_string(), &mut player, &mut world, &mut events).unwrap();
+            assert_eq!(player.current_room, "vault");
+        }
+
+        #[test]
+        fn keys_leave_the_other_requirements_in_place() {
+            let mut world = gated(json!({ "key": "Iron Key", "flag": "lever_pulled" }));
+            let mut player = player_in("hall");
+            player.add_item(key());
+            let mut events = Vec::new();
+
+            use_item("Iron Key", &mut player, &mut world, &mut events);
+            let lock = &world.rooms["hall"].locks["north"];
+            assert!(lock.key.is_none());
+            assert_eq!(lock.flag.as_deref(), Some("lever_pulled"));
+
+            move_player("north".to_string(), &mut player, &mut world, &mut events).unwrap();
+            assert_eq!(player.current_room, "hall");
+        }
+
+        fn examined(target: &str, player: &Player, world: &World) -> Vec<GameEvent> {
+            let mut events = Vec::new();
+            examine(target, player, world, &mut events);
+            events
+        }
+
+        fn study_room() -> World {
+            world(json!({
+                "study": {
+                    "id": "study",
+                    "description": "",
+                    "items": [{
+                        "name": "Old Map",
+                        "description": "A map of the valley.",
+                        "flavor": "Someone has circled the ruins.",
+                        "item_type": "Utility",
+                        "power": null,
+                        "usable_on": null
+                    }],
+                    "features": { "Bookshelf": "Dusty tomes line the shelves." },
+                    "containers": [{ "name": "desk", "description": "An oak writing desk." }],
+                    "enemy": "Examine Test Ghoul"
+                }
+            }))
+        }
+
+        #[test]
+        fn examining_items_keeps_their_description_and_flavor() {
+            let world = study_room();
+            let mut player = player_in("study");
+            player.add_item(key());
+
+            let events = examined("old map", &player, &world);
+            assert!(matches!(
+                &events[..],
+                [GameEvent::ItemExamined(item)]
+                    if item.description == "A map of the valley."
+                        && item.flavor.as_deref() == Some("Someone has circled the ruins.")
+            ));
+            assert!(matches!(
+                &examined("IRON KEY", &player, &world)[..],
+                [GameEvent::ItemExamined(item)] if item.name == "Iron Key"
+            ));
+        }
+
+        #[test]
+        fn examines_the_enemy_features_and_containers() {
+            // The registry is shared by every test, so only add an entry no other test uses
+            let ghoul: crate::enemies::Enemy = serde_json::from_value(json!({
+                "name": "Examine Test Ghoul", "description": "It smells of the grave.", "health": 25, "attack": 6, "xp_reward": 1
+            }))
+            .unwrap();
+            crate::enemies::ENEMY_LIST.write().unwrap().insert(ghoul.name.clone(), ghoul);
+            let world = study_room();
+            let player = player_in("study");
+
+            assert!(matches!(
+                &examined("examine test ghoul", &player, &world)[..],
+                [GameEvent::EnemyExamined { description, health: 25, .. }] if description == "It smells of the grave."
+            ));
+            assert!(matches!(
+                &examined("bookshelf", &player, &world)[..],
+                [GameEvent::FeatureExamined { name, .. }] if name == "Bookshelf"
+            ));
+            assert!(matches!(
+                &examined("Desk", &player, &world)[..],
+                [GameEvent::FeatureExamined { description, .. }] if description == "An oak writing desk."
+            ));
+        }
+
+        #[test]
+        fn examining_nothing_known_says_so() {
+            let world = study_room();
+            let player = player_in("study");
+
+            assert!(matches!(
+                &examined("window", &player, &world)[..],
+                [GameEvent::NothingToExamine(target)] if target == "window"
+            ));
+            assert!(matches!(&examined("", &player, &world)[..], [GameEvent::RoomDescribed(_)]));
+        }
+
+        fn herbs(quantity: u32) -> Item {
+            serde_json::from_value(json!({
+                "name": "Healing Herb", "item_type": "Healing", "power": 10, "usable_on": null,
+                "stackable": true, "quantity": quantity
+            }))
+            .unwrap()
+        }
+
+        fn storeroom(chest: Value) -> World {
+            world(json!({ "store": { "id": "store", "description": "", "containers": [chest] } }))
+        }
+
+        #[test]
+        fn dropped_items_stay_in_the_room_through_a_save() {
+            let mut world = storeroom(json!({ "name": "chest" }));
+            let mut player = player_in("store");
+            player.add_item(herbs(3));
+            let mut events = Vec::new();
+
+            drop_item("healing herb", Some(2), &mut player, &mut world, &mut events);
+            drop_item("healing herb", None, &mut player, &mut world, &mut events);
+
+            assert!(player.inventory.is_empty());
+            let world: World = serde_json::from_str(&serde_json::to_string(&world).unwrap()).unwrap();
+            let floor = &world.rooms["store"].items;
+            assert_eq!(floor.len(), 1);
+            assert_eq!(floor[0].quantity, 3);
+        }
+
+        #[test]
+        fn dropping_something_not_carried_changes_nothing() {
+            let mut world = storeroom(json!({ "name": "chest" }));
+            let mut player = player_in("store");
+            let mut events = Vec::new();
+
+            drop_item("Healing Herb", None, &mut player, &mut world, &mut events);
+
+            assert!(world.rooms["store"].items.is_empty());
+            assert!(matches!(&events[..], [GameEvent::NotInInventory(_)]));
+        }
+
+        #[test]
+        fn items_go_only_into_open_containers() {
+            let mut world = storeroom(json!({ "name": "chest" }));
+            let mut player = player_in("store");
+            player.add_item(herbs(2));
+            let mut events = Vec::new();
+
+            put_item("Healing Herb", Some(1), "chest", &mut player, &mut world, &mut events);
+            assert_eq!(player.inventory[0].quantity, 2);
+            assert!(matches!(&events[..], [GameEvent::ContainerClosed(_)]));
+
+            open_container("chest", &mut player, &mut world, &mut events);
+            put_item("Healing Herb", Some(1), "chest", &mut player, &mut world, &mut events);
+            assert_eq!(player.inventory[0].quantity, 1);
+            assert_eq!(world.rooms["store"].containers[0].items[0].quantity, 1);
+        }
+
+        #[test]
+        fn locked_containers_spend_their_key() {
+            let mut world = storeroom(json!({ "name": "chest", "lock": { "key": "Iron Key" } }));
+            let mut player = player_in("store");
+            let mut events = Vec::new();
+
+            open_container("chest", &mut player, &mut world, &mut events);
+            assert!(!world.rooms["store"].containers[0].open);
+            assert!(matches!(&events[..], [GameEvent::ContainerLocked { .. }]));
+
+            player.add_item(key());
+            open_container("chest", &mut player, &mut world, &mut events);
+            let chest = &world.rooms["store"].containers[0];
+            assert!(chest.open && chest.lock.is_none());
+            assert!(player.inventory.is_empty());
+        }
+
+        #[test]
+        fn keys_are_kept_until_every_other_requirement_is_met() {
+            let mut world = storeroom(json!({ "name": "chest", "lock": { "key": "Iron Key", "flag": "blessed" } }));
+            let mut player = player_in("store");
+            player.add_item(key());
+            let mut events = Vec::new();
+
+            open_container("chest", &mut player, &mut world, &mut events);
+
+            assert!(!world.rooms["store"].containers[0].open);
+            assert_eq!(player.inventory.len(), 1);
+        }
+
+        #[test]
+        fn hidden_containers_turn_up_when_searching() {
+            let mut world = storeroom(json!({ "name": "loose brick", "hidden": true, "items": [herbs(2)] }));
+            let mut player = player_in("store");
+            let mut events = Vec::new();
+
+            open_container("loose brick", &mut player, &mut world, &mut events);
+            assert!(matches!(&events[..], [GameEvent::NoSuchContainer(_)]));
+
+            search("", &mut player, &mut world, &mut events);
+            search("loose brick", &mut player, &mut world, &mut events);
+            let limits = InventoryLimits::default();
+            take_from_container("healing herb", Some(1), "loose brick", &limits, &mut player, &mut world, &mut events);
+
+            assert_eq!(player.inventory[0].quantity, 1);
+            assert_eq!(world.rooms["store"].containers[0].items[0].quantity, 1);
+        }
+
+        #[test]
+        fn closed_containers_keep_their_contents() {
+            let mut world = storeroom(json!({ "name": "chest", "items": [herbs(1)] }));
+            let mut player = player_in("store");
+            let mut events = Vec::new();
+
+            let limits = InventoryLimits::default();
+            take_from_container("Healing Herb", None, "chest", &limits, &mut player, &mut world, &mut events);
+
+            assert!(player.inventory.is_empty());
+            assert!(matches!(&events[..], [GameEvent::ContainerClosed(_)]));
+        }
+
+        #[test]
+        fn rooms_saved_without_triggers_get_the_level_ones() {
+            let level = world(json!({
+                "hall": { "id": "hall", "description": "", "triggers": [{ "on": "on_enter" }] },
+                "crypt": { "id": "crypt", "description": "", "triggers": [{ "on": "on_enter" }, { "on": "on_take" }] }
+            }));
+            let mut saved = world(json!({
+                "hall": { "id": "hall", "description": "" },
+                "crypt": { "id": "crypt", "description": "", "triggers": [{ "on": "on_enter", "once": true, "fired": true }] }
+            }));
+
+            restore_triggers(&mut saved, level);
+
+            assert_eq!(saved.rooms["hall"].triggers.len(), 1);
+            // Rooms that kept their triggers keep their progress too
+            assert_eq!(saved.rooms["crypt"].triggers.len(), 1);
+            assert!(saved.rooms["crypt"].triggers[0].fired);
+        }
     }
@@ -0,0 +1,54 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Writes a campaign whose only problem is a one-way exit, which is a warning
+fn warning_only_assets() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("shards_of_aether_cli_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for (file, key) in [
+        ("items.json", "items"),
+        ("enemies.json", "enemies"),
+        ("quests.json", "quests"),
+        ("recipes.json", "recipes"),
+        ("npcs.json", "npcs"),
+    ] {
+        fs::write(dir.join(file), format!("{{ \"{}\": {{}} }}", key)).unwrap();
+    }
+    fs::write(
+        dir.join("campaign.json"),
+        r#"{ "start": "one", "levels": [{ "id": "one", "file": "one.json", "title": "One", "start_room": "hall" }] }"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("one.json"),
+        r#"{ "rooms": {
+            "hall": { "id": "hall", "description": "", "exits": { "east": "yard" } },
+            "yard": { "id": "yard", "description": "", "x": 1 }
+        } }"#,
+    )
+    .unwrap();
+    dir
+}
+
+fn validate(assets: &PathBuf, strict: bool) -> (bool, String) {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_shards_of_aether"));
+    command.arg("--assets").arg(assets).arg("--no-color").arg("validate");
+    if strict {
+        command.arg("--strict");
+    }
+    let output = command.output().unwrap();
+    (output.status.success(), String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[test]
+fn strict_turns_warnings_into_a_failing_exit() {
+    let assets = warning_only_assets();
+    let (relaxed, report) = validate(&assets, false);
+    let (strict, _) = validate(&assets, true);
+    fs::remove_dir_all(&assets).unwrap();
+
+    assert!(report.contains("one-way"), "{}", report);
+    assert!(relaxed);
+    assert!(!strict);
+}